@@ -0,0 +1,378 @@
+use super::{Chromosome, Decoder, Evaluated, Evaluation, GeneticAlgorithm, Individual};
+use rand_chacha::ChaCha8Rng;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::{fmt, fs, io};
+
+pub const CHECKPOINT_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum CheckpointError {
+    Io(io::Error),
+    UnsupportedVersion { version: u32 },
+    Malformed(String),
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "checkpoint I/O failed: {}", err),
+            Self::UnsupportedVersion { version } => write!(
+                f,
+                "unsupported checkpoint version {} (expected {})",
+                version, CHECKPOINT_VERSION
+            ),
+            Self::Malformed(reason) => write!(f, "malformed checkpoint: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {}
+
+impl From<io::Error> for CheckpointError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+// A saved generation: the algorithm with its operators and counters, the
+// state of the random number generator and the population along with its
+// evaluations. Evolving the resumed population with the resumed algorithm
+// and generator continues the run exactly as if it had never stopped.
+// The state of an `Evolution` isn't saved though: a new one starts with the
+// best fitness of the resumed population, no stagnant generations and the
+// whole time budget, so its stopping conditions can trigger later.
+// The version is only checked when loading, see `Header`
+#[derive(Deserialize)]
+pub struct Checkpoint<C, M, S, G = f32> {
+    algorithm: GeneticAlgorithm<C, M, S>,
+    rng: ChaCha8Rng,
+    chromosomes: Vec<Chromosome<G>>,
+    evaluations: Vec<Evaluation>,
+}
+
+// Borrowing counterpart of `Checkpoint` used for saving
+#[derive(Serialize)]
+struct CheckpointRef<'a, C, M, S, G> {
+    version: u32,
+    algorithm: &'a GeneticAlgorithm<C, M, S>,
+    rng: &'a ChaCha8Rng,
+    chromosomes: Vec<&'a Chromosome<G>>,
+    evaluations: Vec<Evaluation>,
+}
+
+// What a run continues with after a checkpoint
+type Resumed<C, M, S, I> = (GeneticAlgorithm<C, M, S>, ChaCha8Rng, Vec<I>);
+
+// Read first, so that checkpoints of other versions are reported as such
+// instead of as malformed
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+impl<C, M, S, G> Checkpoint<C, M, S, G> {
+    pub fn save<I>(
+        path: impl AsRef<Path>,
+        algorithm: &GeneticAlgorithm<C, M, S>,
+        rng: &ChaCha8Rng,
+        population: &[I],
+    ) -> Result<(), CheckpointError>
+    where
+        C: Serialize,
+        M: Serialize,
+        S: Serialize,
+        G: Serialize,
+        I: Individual<Gene = G>,
+    {
+        let checkpoint = CheckpointRef {
+            version: CHECKPOINT_VERSION,
+            algorithm,
+            rng,
+            chromosomes: population.iter().map(Individual::chromosome).collect(),
+            evaluations: population
+                .iter()
+                .map(|individual| {
+                    Evaluation::new(individual.fitness())
+                        .with_objectives(individual.objectives())
+                        .with_constraint_violation(individual.constraint_violation())
+                })
+                .collect(),
+        };
+        let json = serde_json::to_string_pretty(&checkpoint)
+            .map_err(|err| CheckpointError::Malformed(err.to_string()))?;
+
+        // written next to the checkpoint and then renamed over it, so that
+        // a save that fails halfway leaves the previous checkpoint intact
+        let path = path.as_ref();
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        fs::write(&temporary, json)?;
+        fs::rename(&temporary, path)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CheckpointError>
+    where
+        C: DeserializeOwned,
+        M: DeserializeOwned,
+        S: DeserializeOwned,
+        G: DeserializeOwned,
+    {
+        let json = fs::read_to_string(path)?;
+        let header = serde_json::from_str::<Header>(&json)
+            .map_err(|err| CheckpointError::Malformed(err.to_string()))?;
+        if header.version != CHECKPOINT_VERSION {
+            return Err(CheckpointError::UnsupportedVersion {
+                version: header.version,
+            });
+        }
+
+        let checkpoint = serde_json::from_str::<Self>(&json)
+            .map_err(|err| CheckpointError::Malformed(err.to_string()))?;
+        if checkpoint.chromosomes.len() != checkpoint.evaluations.len() {
+            return Err(CheckpointError::Malformed(
+                "got a different number of chromosomes and evaluations".to_string(),
+            ));
+        }
+        Ok(checkpoint)
+    }
+
+    // Number of generations evolved before the checkpoint was saved
+    pub fn generation(&self) -> usize {
+        self.algorithm.generation
+    }
+
+    // Evaluations of the saved population, in population order
+    pub fn evaluations(&self) -> &[Evaluation] {
+        &self.evaluations
+    }
+
+    pub fn chromosomes(&self) -> &[Chromosome<G>] {
+        &self.chromosomes
+    }
+
+    // The individuals are created again from their chromosomes, which
+    // recomputes their fitness, the saved evaluations are only used by
+    // `resume_evaluated`
+    pub fn resume<I>(self) -> Resumed<C, M, S, I>
+    where
+        I: Individual<Gene = G>,
+    {
+        let population = self.chromosomes.into_iter().map(I::create).collect();
+        (self.algorithm, self.rng, population)
+    }
+
+    // Like `resume`, for populations evolved by the `*_evaluated` methods,
+    // the chromosomes are decoded again but keep their saved evaluations
+    pub fn resume_evaluated<P, D>(self, decoder: &D) -> Resumed<C, M, S, Evaluated<P, G>>
+    where
+        D: Decoder<G, Phenotype = P>,
+    {
+        let population = self
+            .chromosomes
+            .into_iter()
+            .zip(self.evaluations)
+            .map(|(chromosome, evaluation)| {
+                let phenotype = decoder.decode(&chromosome);
+                Evaluated::new(chromosome, phenotype, evaluation)
+            })
+            .collect();
+        (self.algorithm, self.rng, population)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        Identity, NormalMutation, OneFifthRuleMutation, RouletteWheelSelection, Sum,
+        TestIndividual, TournamentSelection, UniformCrossover,
+    };
+    use rand::SeedableRng;
+    use std::path::PathBuf;
+
+    fn path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "genetic-algorithm-{}-{}.json",
+            name,
+            std::process::id()
+        ))
+    }
+
+    fn population() -> Vec<TestIndividual> {
+        (0..8)
+            .map(|n| {
+                TestIndividual::create(vec![n as f32, 1.0, (n % 3) as f32].into_iter().collect())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn resumed_run_is_identical() {
+        let path = path("resume");
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut ga = GeneticAlgorithm::new(
+            UniformCrossover::new(),
+            OneFifthRuleMutation::new(0.5, 0.5, 0.8),
+            TournamentSelection::new(2, 0.9),
+        )
+        .with_elitism(1);
+
+        let mut population = population();
+        for _ in 0..5 {
+            population = ga.evolve(&mut rng, &population).0;
+        }
+        Checkpoint::save(&path, &ga, &rng, &population).unwrap();
+
+        for _ in 0..5 {
+            population = ga.evolve(&mut rng, &population).0;
+        }
+
+        let checkpoint = Checkpoint::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(checkpoint.generation(), 5);
+        assert_eq!(checkpoint.evaluations().len(), 8);
+
+        let (mut resumed_ga, mut resumed_rng, mut resumed_population): (
+            GeneticAlgorithm<UniformCrossover, OneFifthRuleMutation, TournamentSelection>,
+            _,
+            Vec<TestIndividual>,
+        ) = checkpoint.resume();
+        for _ in 0..5 {
+            resumed_population = resumed_ga.evolve(&mut resumed_rng, &resumed_population).0;
+        }
+
+        assert_eq!(resumed_population, population);
+        assert_eq!(resumed_ga.generation(), ga.generation());
+        assert_eq!(
+            resumed_ga.mutation_method.sigma(),
+            ga.mutation_method.sigma()
+        );
+    }
+
+    #[test]
+    fn resumed_evaluated_run_is_identical() {
+        let path = path("resume-evaluated");
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut ga = GeneticAlgorithm::new(
+            UniformCrossover::new(),
+            NormalMutation::new(0.5, 0.5),
+            RouletteWheelSelection::new(),
+        );
+        let chromosomes = population()
+            .iter()
+            .map(|individual| individual.chromosome().clone())
+            .collect();
+
+        let mut population = Evaluated::evaluate_all(&Identity, &Sum::default(), chromosomes);
+        for _ in 0..3 {
+            population = ga
+                .evolve_evaluated(&mut rng, &population, &Identity, &Sum::default())
+                .0;
+        }
+        Checkpoint::save(&path, &ga, &rng, &population).unwrap();
+        let saved = population
+            .iter()
+            .map(|individual| individual.evaluation().clone())
+            .collect::<Vec<Evaluation>>();
+
+        for _ in 0..3 {
+            population = ga
+                .evolve_evaluated(&mut rng, &population, &Identity, &Sum::default())
+                .0;
+        }
+
+        let checkpoint = Checkpoint::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(checkpoint.evaluations(), saved.as_slice());
+        let (mut resumed_ga, mut resumed_rng, mut resumed_population): (
+            GeneticAlgorithm<UniformCrossover, NormalMutation, RouletteWheelSelection>,
+            _,
+            _,
+        ) = checkpoint.resume_evaluated(&Identity);
+        let evaluator = Sum::default();
+        for _ in 0..3 {
+            resumed_population = resumed_ga
+                .evolve_evaluated(&mut resumed_rng, &resumed_population, &Identity, &evaluator)
+                .0;
+        }
+
+        // resuming doesn't evaluate the saved population again
+        assert_eq!(*evaluator.batches.lock().unwrap(), vec![8; 3]);
+
+        let chromosomes = |population: &[Evaluated<Vec<f32>>]| {
+            population
+                .iter()
+                .map(|individual| individual.chromosome().clone())
+                .collect::<Vec<Chromosome>>()
+        };
+        assert_eq!(chromosomes(&resumed_population), chromosomes(&population));
+    }
+
+    #[test]
+    fn save_replaces_the_previous_checkpoint() {
+        let path = path("replace");
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut ga = GeneticAlgorithm::new(
+            UniformCrossover::new(),
+            NormalMutation::new(0.5, 0.5),
+            RouletteWheelSelection::new(),
+        );
+
+        let mut population = population();
+        Checkpoint::save(&path, &ga, &rng, &population).unwrap();
+        population = ga.evolve(&mut rng, &population).0;
+        Checkpoint::save(&path, &ga, &rng, &population).unwrap();
+
+        let checkpoint =
+            Checkpoint::<UniformCrossover, NormalMutation, RouletteWheelSelection>::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(checkpoint.unwrap().generation(), 1);
+        let mut temporary = path.into_os_string();
+        temporary.push(".tmp");
+        assert!(!PathBuf::from(temporary).exists());
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let path = path("version");
+        let ga = GeneticAlgorithm::new(
+            UniformCrossover::new(),
+            NormalMutation::new(0.5, 0.5),
+            RouletteWheelSelection::new(),
+        );
+        let rng = ChaCha8Rng::from_seed(Default::default());
+        Checkpoint::save(&path, &ga, &rng, &population()).unwrap();
+
+        let json = fs::read_to_string(&path)
+            .unwrap()
+            .replace("\"version\": 1", "\"version\": 9");
+        fs::write(&path, json).unwrap();
+        let checkpoint =
+            Checkpoint::<UniformCrossover, NormalMutation, RouletteWheelSelection>::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        match checkpoint {
+            Err(CheckpointError::UnsupportedVersion { version }) => assert_eq!(version, 9),
+            _ => panic!("expected an unsupported version"),
+        }
+    }
+
+    #[test]
+    fn malformed_checkpoint() {
+        let path = path("malformed");
+        fs::write(&path, "{\"version\": 1}").unwrap();
+
+        let checkpoint =
+            Checkpoint::<UniformCrossover, NormalMutation, RouletteWheelSelection>::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(checkpoint, Err(CheckpointError::Malformed(_))));
+        assert!(matches!(
+            Checkpoint::<UniformCrossover, NormalMutation, RouletteWheelSelection>::load(&path),
+            Err(CheckpointError::Io(_))
+        ));
+    }
+}
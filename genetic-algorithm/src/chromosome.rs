@@ -1,43 +1,82 @@
+use super::Gene;
 use std::iter::{FromIterator, IntoIterator};
 use std::ops::Index;
 
-#[derive(Debug)]
-pub struct Chromosome {
-    genes: Vec<f32>,
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "checkpoint", serde(transparent))]
+pub struct Chromosome<G = f32> {
+    genes: Vec<G>,
 }
 
-impl Chromosome {
+impl<G> Chromosome<G> {
     pub fn len(&self) -> usize {
         self.genes.len()
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &f32> {
+    pub fn is_empty(&self) -> bool {
+        self.genes.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &G> {
         self.genes.iter()
     }
 
-    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut f32> {
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut G> {
         self.genes.iter_mut()
     }
+
+    pub fn as_slice(&self) -> &[G] {
+        &self.genes
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [G] {
+        &mut self.genes
+    }
+}
+
+impl<G> Chromosome<G>
+where
+    G: Gene,
+{
+    // Numeric values of the genes, see `Gene::value`
+    pub fn values(&self) -> Vec<f32> {
+        self.iter().map(Gene::value).collect()
+    }
+
+    // Euclidean distance between the gene values of two chromosomes
+    pub fn distance(&self, other: &Self) -> f32 {
+        euclidean_distance(&self.values(), &other.values())
+    }
+}
+
+pub fn euclidean_distance(a: &[f32], b: &[f32]) -> f32 {
+    assert_eq!(a.len(), b.len());
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (a - b).powi(2))
+        .sum::<f32>()
+        .sqrt()
 }
 
-impl Index<usize> for Chromosome {
-    type Output = f32;
+impl<G> Index<usize> for Chromosome<G> {
+    type Output = G;
     fn index(&self, index: usize) -> &Self::Output {
         &self.genes[index]
     }
 }
 
-impl FromIterator<f32> for Chromosome {
-    fn from_iter<T: IntoIterator<Item = f32>>(iter: T) -> Self {
+impl<G> FromIterator<G> for Chromosome<G> {
+    fn from_iter<T: IntoIterator<Item = G>>(iter: T) -> Self {
         Self {
             genes: iter.into_iter().collect(),
         }
     }
 }
 
-impl IntoIterator for Chromosome {
-    type Item = f32;
-    type IntoIter = impl Iterator<Item = f32>;
+impl<G> IntoIterator for Chromosome<G> {
+    type Item = G;
+    type IntoIter = impl Iterator<Item = G>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.genes.into_iter()
@@ -57,7 +96,7 @@ mod test {
 
     #[test]
     fn chromosome_methods() {
-        let chromosome = Chromosome::from_iter(vec![1.0, 2.0, 3.0, 4.0]);
+        let chromosome = Chromosome::<f32>::from_iter(vec![1.0, 2.0, 3.0, 4.0]);
         assert_eq!(chromosome.len(), 4);
 
         assert!((chromosome[0] - 1.0).abs() < f32::EPSILON);
@@ -72,7 +111,7 @@ mod test {
         assert!((genes[2] - 3.0).abs() < f32::EPSILON);
         assert!((genes[3] - 4.0).abs() < f32::EPSILON);
 
-        let mut chromosome = Chromosome::from_iter(vec![1.0, 2.0, 3.0, 4.0]);
+        let mut chromosome = Chromosome::<f32>::from_iter(vec![1.0, 2.0, 3.0, 4.0]);
 
         chromosome.iter_mut().for_each(|x| *x += 1.0);
 
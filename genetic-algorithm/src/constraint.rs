@@ -0,0 +1,442 @@
+// Constraint handling: bounds keep the genes of a chromosome inside their
+// ranges, while the selection methods below deal with individuals that
+// violate a constraint (see `Individual::constraint_violation`)
+
+use super::niching::select_with_fitness;
+use super::{Chromosome, Gene, Individual, SelectionMethod};
+use rand::{Rng, RngCore};
+
+// How a gene that left its range is brought back into it
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub enum Repair {
+    // Moves the gene to the nearest bound
+    Clamp,
+    // Mirrors the gene at the bound it crossed, as often as needed
+    Reflect,
+    // Lets the gene re-enter the range at the opposite bound
+    Wrap,
+    // Draws a new gene uniformly from the range
+    Resample,
+}
+
+// Per-gene `(min, max)` ranges along with the way they are enforced
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bounds {
+    ranges: Vec<(f32, f32)>,
+    repair: Repair,
+}
+
+impl Bounds {
+    pub fn new(ranges: Vec<(f32, f32)>) -> Self {
+        assert!(
+            ranges.iter().all(|(min, max)| min <= max),
+            "got a range with min > max"
+        );
+        Self {
+            ranges,
+            repair: Repair::Clamp,
+        }
+    }
+
+    // The same range for each of `len` genes
+    pub fn uniform(len: usize, min: f32, max: f32) -> Self {
+        Self::new(vec![(min, max); len])
+    }
+
+    pub fn with_repair(mut self, repair: Repair) -> Self {
+        self.repair = repair;
+        self
+    }
+
+    pub fn ranges(&self) -> &[(f32, f32)] {
+        &self.ranges
+    }
+
+    pub fn contains<G>(&self, chromosome: &Chromosome<G>) -> bool
+    where
+        G: Gene,
+    {
+        self.violation(chromosome) == 0.0
+    }
+
+    // Total distance of the genes from their ranges, 0 within bounds
+    pub fn violation<G>(&self, chromosome: &Chromosome<G>) -> f32
+    where
+        G: Gene,
+    {
+        assert_eq!(chromosome.len(), self.ranges.len());
+        chromosome
+            .iter()
+            .zip(self.ranges.iter())
+            .map(|(gene, &(min, max))| {
+                let value = gene.value();
+                (min - value).max(value - max).max(0.0)
+            })
+            .sum()
+    }
+
+    // Genes within their ranges are left untouched
+    pub fn repair<G>(&self, rng: &mut dyn RngCore, chromosome: &mut Chromosome<G>)
+    where
+        G: Gene,
+    {
+        assert_eq!(chromosome.len(), self.ranges.len());
+        for (gene, &(min, max)) in chromosome.iter_mut().zip(self.ranges.iter()) {
+            let value = gene.value();
+            if value >= min && value <= max {
+                continue;
+            }
+            let width = max - min;
+            let repaired = match self.repair {
+                Repair::Clamp => value.max(min).min(max),
+                _ if width == 0.0 => min,
+                Repair::Reflect => {
+                    let offset = (value - min).rem_euclid(2.0 * width);
+                    min + if offset > width {
+                        2.0 * width - offset
+                    } else {
+                        offset
+                    }
+                }
+                Repair::Wrap => min + (value - min).rem_euclid(width),
+                Repair::Resample => rng.gen_range(min..=max),
+            };
+            *gene = G::from_value(repaired);
+        }
+    }
+}
+
+// Static penalty: the wrapped selection method sees the fitness reduced by
+// `coefficient` times the constraint violation
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct Penalty<S> {
+    selection_method: S,
+    coefficient: f32,
+}
+
+impl<S> Penalty<S>
+where
+    S: SelectionMethod,
+{
+    pub fn new(selection_method: S, coefficient: f32) -> Self {
+        assert!(coefficient >= 0.0);
+        Self {
+            selection_method,
+            coefficient,
+        }
+    }
+
+    // Penalized fitness of every individual, in population order, shifted up
+    // if any of it is negative
+    pub fn penalized_fitness<I>(&self, population: &[I]) -> Vec<f32>
+    where
+        I: Individual,
+    {
+        non_negative(
+            population
+                .iter()
+                .map(|individual| {
+                    individual.fitness() - self.coefficient * individual.constraint_violation()
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<S> SelectionMethod for Penalty<S>
+where
+    S: SelectionMethod,
+{
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        let fitness = self.penalized_fitness(population);
+        select_with_fitness(&self.selection_method, rng, population, fitness, count)
+    }
+
+    fn selects_in_batches(&self) -> bool {
+        true
+    }
+}
+
+// Feasibility rules (Deb): feasible individuals are ranked by their fitness,
+// infeasible ones by their constraint violation and every feasible individual
+// ranks above every infeasible one
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeasibilityRules<S> {
+    selection_method: S,
+}
+
+impl<S> FeasibilityRules<S>
+where
+    S: SelectionMethod,
+{
+    pub fn new(selection_method: S) -> Self {
+        Self { selection_method }
+    }
+
+    // Fitness of every individual as ranked by the rules, in population order:
+    // infeasible individuals get the worst feasible fitness (or the worst
+    // fitness if none is feasible) minus their violation, and everything is
+    // shifted up if the worst of them is negative
+    pub fn ranked_fitness<I>(population: &[I]) -> Vec<f32>
+    where
+        I: Individual,
+    {
+        let violations = population
+            .iter()
+            .map(Individual::constraint_violation)
+            .collect::<Vec<f32>>();
+        let worst_feasible = population
+            .iter()
+            .zip(violations.iter())
+            .filter(|(_, &violation)| violation <= 0.0)
+            .map(|(individual, _)| individual.fitness())
+            .fold(f32::INFINITY, f32::min);
+        let baseline = if worst_feasible.is_finite() {
+            worst_feasible
+        } else {
+            population
+                .iter()
+                .map(Individual::fitness)
+                .fold(f32::INFINITY, f32::min)
+        };
+
+        non_negative(
+            population
+                .iter()
+                .zip(violations)
+                .map(|(individual, violation)| {
+                    if violation <= 0.0 {
+                        individual.fitness()
+                    } else {
+                        baseline - violation
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<S> SelectionMethod for FeasibilityRules<S>
+where
+    S: SelectionMethod,
+{
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        let fitness = Self::ranked_fitness(population);
+        select_with_fitness(&self.selection_method, rng, population, fitness, count)
+    }
+
+    fn selects_in_batches(&self) -> bool {
+        true
+    }
+}
+
+// Subtracting the minimum keeps the order of the fitness values, so wrapped
+// methods that need non-negative fitness (e.g. roulette wheel selection) work
+fn non_negative(mut fitness: Vec<f32>) -> Vec<f32> {
+    let min = fitness.iter().cloned().fold(f32::INFINITY, f32::min);
+    if min < 0.0 {
+        for value in fitness.iter_mut() {
+            *value -= min;
+        }
+    }
+    fitness
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{histogram, RouletteWheelSelection, TournamentSelection};
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng as Cc8;
+    use std::collections::BTreeMap;
+    use std::iter::FromIterator;
+
+    // Fitness and constraint violation given directly
+    struct Constrained {
+        fitness: f32,
+        violation: f32,
+        chromosome: Chromosome,
+    }
+
+    impl Individual for Constrained {
+        type Gene = f32;
+
+        fn fitness(&self) -> f32 {
+            self.fitness
+        }
+
+        fn constraint_violation(&self) -> f32 {
+            self.violation
+        }
+
+        fn chromosome(&self) -> &Chromosome {
+            &self.chromosome
+        }
+
+        fn create(_chromosome: Chromosome) -> Self {
+            unreachable!("selection methods never create individuals")
+        }
+    }
+
+    fn constrained(individuals: &[(f32, f32)]) -> Vec<Constrained> {
+        individuals
+            .iter()
+            .map(|&(fitness, violation)| Constrained {
+                fitness,
+                violation,
+                chromosome: Chromosome::from_iter(vec![fitness]),
+            })
+            .collect()
+    }
+
+    fn repaired(repair: Repair) -> Vec<f32> {
+        let mut rng = Cc8::from_seed(Default::default());
+        let bounds = Bounds::uniform(5, -1.0, 1.0).with_repair(repair);
+        let mut chromosome = Chromosome::<f32>::from_iter(vec![0.5, 1.5, -1.25, 4.5, -1.0]);
+        bounds.repair(&mut rng, &mut chromosome);
+        assert!(bounds.contains(&chromosome));
+        chromosome.into_iter().collect()
+    }
+
+    #[test]
+    fn clamp() {
+        let actual = repaired(Repair::Clamp);
+        assert_relative_eq!(actual.as_slice(), [0.5, 1.0, -1.0, 1.0, -1.0].as_ref());
+    }
+
+    #[test]
+    fn reflect() {
+        let actual = repaired(Repair::Reflect);
+        assert_relative_eq!(actual.as_slice(), [0.5, 0.5, -0.75, 0.5, -1.0].as_ref());
+    }
+
+    #[test]
+    fn wrap() {
+        let actual = repaired(Repair::Wrap);
+        assert_relative_eq!(actual.as_slice(), [0.5, -0.5, 0.75, 0.5, -1.0].as_ref());
+    }
+
+    #[test]
+    fn resample() {
+        let actual = repaired(Repair::Resample);
+        // genes within bounds are kept
+        assert_relative_eq!(actual[0], 0.5);
+        assert_relative_eq!(actual[4], -1.0);
+    }
+
+    #[test]
+    fn repair_integer_genes() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let bounds = Bounds::new(vec![(0.0, 10.0), (0.0, 3.0)]).with_repair(Repair::Reflect);
+        let mut chromosome = Chromosome::from_iter(vec![12, -2]);
+        bounds.repair(&mut rng, &mut chromosome);
+
+        assert_eq!(chromosome.into_iter().collect::<Vec<i32>>(), vec![8, 2]);
+    }
+
+    #[test]
+    fn violation() {
+        let bounds = Bounds::new(vec![(0.0, 1.0), (-1.0, 1.0), (2.0, 2.0)]);
+
+        let inside = Chromosome::<f32>::from_iter(vec![0.5, -1.0, 2.0]);
+        assert_relative_eq!(bounds.violation(&inside), 0.0);
+        let outside = Chromosome::<f32>::from_iter(vec![1.5, -3.0, 2.25]);
+        assert_relative_eq!(bounds.violation(&outside), 2.75);
+    }
+
+    #[test]
+    fn penalized_fitness() {
+        let method = Penalty::new(RouletteWheelSelection::new(), 2.0);
+        let positive = constrained(&[(4.0, 0.0), (5.0, 1.0), (6.0, 0.5)]);
+
+        let actual = method.penalized_fitness(&positive);
+        assert_relative_eq!(actual.as_slice(), [4.0, 3.0, 5.0].as_ref());
+
+        // shifted up by the worst penalized fitness, -1.0
+        let negative = constrained(&[(1.0, 1.0), (5.0, 0.0), (2.0, 0.5)]);
+        let actual = method.penalized_fitness(&negative);
+        assert_relative_eq!(actual.as_slice(), [0.0, 6.0, 2.0].as_ref());
+    }
+
+    #[test]
+    fn ranked_fitness() {
+        let mixed = constrained(&[(4.0, 0.0), (9.0, 1.0), (6.0, 0.0), (8.0, 0.5)]);
+
+        let actual = FeasibilityRules::<RouletteWheelSelection>::ranked_fitness(&mixed);
+        assert_relative_eq!(actual.as_slice(), [4.0, 3.0, 6.0, 3.5].as_ref());
+
+        // without feasible individuals the violation alone decides
+        let infeasible = constrained(&[(4.0, 2.0), (9.0, 1.0)]);
+        let actual = FeasibilityRules::<RouletteWheelSelection>::ranked_fitness(&infeasible);
+        assert_relative_eq!(actual.as_slice(), [2.0, 3.0].as_ref());
+
+        // shifted up by the worst ranked fitness, 1.0 - 3.0
+        let negative = constrained(&[(1.0, 0.0), (2.0, 0.0), (9.0, 3.0)]);
+        let actual = FeasibilityRules::<RouletteWheelSelection>::ranked_fitness(&negative);
+        assert_relative_eq!(actual.as_slice(), [3.0, 4.0, 0.0].as_ref());
+    }
+
+    #[test]
+    fn roulette_wheel_with_negative_penalized_fitness() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let penalty = Penalty::new(RouletteWheelSelection::new(), 100.0);
+        let rules = FeasibilityRules::new(RouletteWheelSelection::new());
+        let population = constrained(&[(1.0, 0.0), (2.0, 0.0), (100.0, 2.0)]);
+
+        // the worst individual ends up with no weight at all
+        assert!(penalty
+            .select_many(&mut rng, &population, 100)
+            .iter()
+            .all(|individual| individual.fitness() != 100.0));
+        assert!(rules
+            .select_many(&mut rng, &population, 100)
+            .iter()
+            .all(|individual| individual.fitness() != 100.0));
+    }
+
+    #[test]
+    fn feasibility_rules() {
+        let method = FeasibilityRules::new(TournamentSelection::new(2, 1.0));
+        // the infeasible individual only wins tournaments against itself
+        let population = constrained(&[(1.0, 0.0), (2.0, 0.0), (100.0, 1.0)]);
+
+        let actual_histogram = histogram(&method, &population);
+
+        let expected_histogram = BTreeMap::from_iter(vec![(1, 315), (2, 571), (100, 114)]);
+
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+}
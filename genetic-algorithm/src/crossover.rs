@@ -1,16 +1,35 @@
 use super::Chromosome;
 
+use rand::seq::index;
 use rand::{Rng, RngCore};
 
-pub trait CrossoverMethod {
+pub trait CrossoverMethod<G = f32> {
     fn crossover(
         &self,
         rng: &mut dyn RngCore,
-        parent_a: &Chromosome,
-        parent_b: &Chromosome,
-    ) -> Chromosome;
+        parent_a: &Chromosome<G>,
+        parent_b: &Chromosome<G>,
+    ) -> Chromosome<G>;
+}
+
+// Boxed crossover methods can be picked at runtime, e.g. a different one for
+// every island of an `IslandModel`
+impl<G, T> CrossoverMethod<G> for Box<T>
+where
+    T: CrossoverMethod<G> + ?Sized,
+{
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome<G>,
+        parent_b: &Chromosome<G>,
+    ) -> Chromosome<G> {
+        (**self).crossover(rng, parent_a, parent_b)
+    }
 }
 
+#[derive(Default)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
 pub struct UniformCrossover;
 
 impl UniformCrossover {
@@ -19,7 +38,151 @@ impl UniformCrossover {
     }
 }
 
-impl CrossoverMethod for UniformCrossover {
+impl<G> CrossoverMethod<G> for UniformCrossover
+where
+    G: Clone,
+{
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome<G>,
+        parent_b: &Chromosome<G>,
+    ) -> Chromosome<G> {
+        assert_eq!(parent_a.len(), parent_b.len());
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(a, b)| if rng.gen_bool(0.5) { a } else { b })
+            .cloned()
+            .collect()
+    }
+}
+
+// Genes before a random cut point come from `parent_a`, the rest from `parent_b`
+#[derive(Default)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct SinglePointCrossover;
+
+impl SinglePointCrossover {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<G> CrossoverMethod<G> for SinglePointCrossover
+where
+    G: Clone,
+{
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome<G>,
+        parent_b: &Chromosome<G>,
+    ) -> Chromosome<G> {
+        KPointCrossover::new(1).crossover(rng, parent_a, parent_b)
+    }
+}
+
+// The chromosome is cut at `points` distinct random positions and the
+// segments are taken alternately from the parents, starting with `parent_a`
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct KPointCrossover {
+    points: usize,
+}
+
+impl KPointCrossover {
+    pub fn new(points: usize) -> Self {
+        assert!(points > 0);
+        Self { points }
+    }
+}
+
+impl<G> CrossoverMethod<G> for KPointCrossover
+where
+    G: Clone,
+{
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome<G>,
+        parent_b: &Chromosome<G>,
+    ) -> Chromosome<G> {
+        assert_eq!(parent_a.len(), parent_b.len());
+        let len = parent_a.len();
+        if len < 2 {
+            return parent_a.clone();
+        }
+
+        // cut points are in 1..len, so that no segment is empty
+        let points = self.points.min(len - 1);
+        let mut cuts = index::sample(rng, len - 1, points)
+            .into_iter()
+            .map(|cut| cut + 1)
+            .collect::<Vec<usize>>();
+        cuts.sort_unstable();
+
+        let mut cuts = cuts.into_iter().peekable();
+        let mut from_a = true;
+        (0..len)
+            .map(|i| {
+                if cuts.peek() == Some(&i) {
+                    cuts.next();
+                    from_a = !from_a;
+                }
+                if from_a {
+                    parent_a[i].clone()
+                } else {
+                    parent_b[i].clone()
+                }
+            })
+            .collect()
+    }
+}
+
+// Weighted average of the parents: child = weight * a + (1 - weight) * b
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct ArithmeticCrossover {
+    weight: f32,
+}
+
+impl ArithmeticCrossover {
+    pub fn new(weight: f32) -> Self {
+        assert!((0.0..=1.0).contains(&weight));
+        Self { weight }
+    }
+}
+
+impl CrossoverMethod for ArithmeticCrossover {
+    fn crossover(
+        &self,
+        _rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| self.weight * a + (1.0 - self.weight) * b)
+            .collect()
+    }
+}
+
+// Blend crossover: every gene is drawn uniformly from the interval spanned by
+// the parents' genes, extended by `alpha` times its length on both sides
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlxAlphaCrossover {
+    alpha: f32,
+}
+
+impl BlxAlphaCrossover {
+    pub fn new(alpha: f32) -> Self {
+        assert!(alpha >= 0.0);
+        Self { alpha }
+    }
+}
+
+impl CrossoverMethod for BlxAlphaCrossover {
     fn crossover(
         &self,
         rng: &mut dyn RngCore,
@@ -30,11 +193,234 @@ impl CrossoverMethod for UniformCrossover {
         parent_a
             .iter()
             .zip(parent_b.iter())
-            .map(|(&a, &b)| if rng.gen_bool(0.5) { a } else { b })
+            .map(|(&a, &b)| {
+                let extension = self.alpha * (a - b).abs();
+                let low = a.min(b) - extension;
+                let high = a.max(b) + extension;
+                if low < high {
+                    rng.gen_range(low..=high)
+                } else {
+                    a
+                }
+            })
             .collect()
     }
 }
 
+// Simulated binary crossover (Deb & Agrawal): mimics the spread of single-point
+// crossover on binary strings, larger distribution indices keep children
+// closer to their parents
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimulatedBinaryCrossover {
+    distribution_index: f32,
+}
+
+impl SimulatedBinaryCrossover {
+    pub fn new(distribution_index: f32) -> Self {
+        assert!(distribution_index >= 0.0);
+        Self { distribution_index }
+    }
+}
+
+impl CrossoverMethod for SimulatedBinaryCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+        let exponent = 1.0 / (self.distribution_index + 1.0);
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| {
+                let u = rng.gen::<f32>();
+                let beta = if u <= 0.5 {
+                    (2.0 * u).powf(exponent)
+                } else {
+                    (1.0 / (2.0 * (1.0 - u))).powf(exponent)
+                };
+                // either of the two symmetric children with equal probability
+                let sign = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+                0.5 * ((a + b) + sign * beta * (a - b))
+            })
+            .collect()
+    }
+}
+
+// The crossovers below are meant for permutations: both parents have to
+// contain the same genes, each exactly once, and so will the child
+
+// Random segment [start, end) of a chromosome with `len` genes, never empty
+fn segment(rng: &mut dyn RngCore, len: usize) -> (usize, usize) {
+    let mut cuts = index::sample(rng, len + 1, 2).into_vec();
+    cuts.sort_unstable();
+    (cuts[0], cuts[1])
+}
+
+fn position<G>(genes: &[G], gene: &G) -> usize
+where
+    G: PartialEq,
+{
+    genes
+        .iter()
+        .position(|other| other == gene)
+        .expect("parents are not permutations of each other")
+}
+
+// Order crossover (OX1): a random segment is copied from `parent_a` and the
+// remaining positions are filled, starting after the segment, with the missing
+// genes in the order they follow each other in `parent_b`
+#[derive(Default)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrderCrossover;
+
+impl OrderCrossover {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<G> CrossoverMethod<G> for OrderCrossover
+where
+    G: Clone + PartialEq,
+{
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome<G>,
+        parent_b: &Chromosome<G>,
+    ) -> Chromosome<G> {
+        assert_eq!(parent_a.len(), parent_b.len());
+        let len = parent_a.len();
+        if len < 2 {
+            return parent_a.clone();
+        }
+
+        let (start, end) = segment(rng, len);
+        let kept = &parent_a.as_slice()[start..end];
+        let mut rest = (0..len)
+            .map(|i| &parent_b[(end + i) % len])
+            .filter(|gene| !kept.contains(gene));
+
+        let mut child = vec![None; len];
+        for (i, gene) in kept.iter().enumerate() {
+            child[start + i] = Some(gene.clone());
+        }
+        for i in 0..len - kept.len() {
+            child[(end + i) % len] = rest.next().cloned();
+        }
+        child
+            .into_iter()
+            .map(|gene| gene.expect("parents are not permutations of each other"))
+            .collect()
+    }
+}
+
+// Partially mapped crossover (PMX): a random segment is copied from
+// `parent_a`, the rest comes from `parent_b`, except that the genes of
+// `parent_b` displaced by the segment are moved to the positions given by the
+// mapping between the two segments
+#[derive(Default)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct PartiallyMappedCrossover;
+
+impl PartiallyMappedCrossover {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<G> CrossoverMethod<G> for PartiallyMappedCrossover
+where
+    G: Clone + PartialEq,
+{
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome<G>,
+        parent_b: &Chromosome<G>,
+    ) -> Chromosome<G> {
+        assert_eq!(parent_a.len(), parent_b.len());
+        let len = parent_a.len();
+        if len < 2 {
+            return parent_a.clone();
+        }
+
+        let (start, end) = segment(rng, len);
+        let a = parent_a.as_slice();
+        let b = parent_b.as_slice();
+        let kept = &a[start..end];
+
+        let mut child = b.to_vec();
+        child[start..end].clone_from_slice(kept);
+        for (i, gene) in b.iter().enumerate().take(end).skip(start) {
+            if kept.contains(gene) {
+                continue;
+            }
+            // follow the mapping until it leads out of the segment
+            let mut target = i;
+            while (start..end).contains(&target) {
+                target = position(b, &a[target]);
+            }
+            child[target] = gene.clone();
+        }
+        child.into_iter().collect()
+    }
+}
+
+// Cycle crossover (CX): the positions are split into cycles that map the
+// parents onto each other, and the cycles are taken alternately from the
+// parents, starting with `parent_a`, so every gene keeps the position it had
+// in one of the parents
+#[derive(Default)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct CycleCrossover;
+
+impl CycleCrossover {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<G> CrossoverMethod<G> for CycleCrossover
+where
+    G: Clone + PartialEq,
+{
+    fn crossover(
+        &self,
+        _rng: &mut dyn RngCore,
+        parent_a: &Chromosome<G>,
+        parent_b: &Chromosome<G>,
+    ) -> Chromosome<G> {
+        assert_eq!(parent_a.len(), parent_b.len());
+        let a = parent_a.as_slice();
+        let b = parent_b.as_slice();
+
+        let mut child = vec![None; a.len()];
+        let mut from_a = true;
+        while let Some(start) = child.iter().position(Option::is_none) {
+            let mut i = start;
+            loop {
+                // with repeated genes a cycle can lead back into another one
+                // instead of to its start
+                assert!(
+                    child[i].is_none(),
+                    "parents are not permutations of each other"
+                );
+                child[i] = Some(if from_a { a[i].clone() } else { b[i].clone() });
+                i = position(a, &b[i]);
+                if i == start {
+                    break;
+                }
+            }
+            from_a = !from_a;
+        }
+        child.into_iter().map(Option::unwrap).collect()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -55,4 +441,143 @@ mod test {
         assert_eq!(diff_a, 49);
         assert_eq!(diff_b, 51);
     }
+
+    fn parents() -> (Chromosome, Chromosome) {
+        let parent_a = (1..=8).map(|n| n as f32).collect();
+        let parent_b = (1..=8).map(|n| -n as f32).collect();
+        (parent_a, parent_b)
+    }
+
+    fn child<C>(method: C) -> Vec<f32>
+    where
+        C: CrossoverMethod,
+    {
+        let mut rng = Cc8::from_seed(Default::default());
+        let (parent_a, parent_b) = parents();
+        method
+            .crossover(&mut rng, &parent_a, &parent_b)
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn single_point_crossover() {
+        let actual = child(SinglePointCrossover::new());
+        let expected = vec![1.0, 2.0, -3.0, -4.0, -5.0, -6.0, -7.0, -8.0];
+        approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn k_point_crossover() {
+        let actual = child(KPointCrossover::new(3));
+        let expected = vec![1.0, 2.0, 3.0, 4.0, 5.0, -6.0, 7.0, -8.0];
+        approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn arithmetic_crossover() {
+        let actual = child(ArithmeticCrossover::new(0.75));
+        let expected = vec![0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 3.5, 4.0];
+        approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn blx_alpha_crossover() {
+        let actual = child(BlxAlphaCrossover::new(0.5));
+        let expected = vec![
+            -1.2510376, 2.6953583, 4.908757, 2.1027918, 5.2388067, -6.42202, 0.97117615, -12.237091,
+        ];
+        approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn simulated_binary_crossover() {
+        let actual = child(SimulatedBinaryCrossover::new(2.0));
+        let expected = vec![
+            -0.72079206,
+            2.2140012,
+            3.0727615,
+            3.1983407,
+            -5.5669327,
+            4.8352957,
+            6.6320076,
+            9.156639,
+        ];
+        approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    fn permutation_child<C>(method: C) -> Vec<usize>
+    where
+        C: CrossoverMethod<usize>,
+    {
+        let mut rng = Cc8::from_seed(Default::default());
+        let parent_a = vec![1, 2, 3, 4, 5, 6, 7, 8].into_iter().collect();
+        let parent_b = vec![8, 6, 4, 2, 7, 5, 3, 1].into_iter().collect();
+        method
+            .crossover(&mut rng, &parent_a, &parent_b)
+            .into_iter()
+            .collect()
+    }
+
+    fn is_permutation(genes: &[usize]) -> bool {
+        let mut sorted = genes.to_vec();
+        sorted.sort_unstable();
+        sorted == (1..=8).collect::<Vec<usize>>()
+    }
+
+    #[test]
+    fn order_crossover() {
+        let actual = permutation_child(OrderCrossover::new());
+        assert!(is_permutation(&actual));
+        assert_eq!(actual, vec![8, 2, 3, 4, 5, 6, 7, 1]);
+    }
+
+    #[test]
+    fn partially_mapped_crossover() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let parent_a = (1..=12).collect();
+        let parent_b = vec![9, 12, 5, 2, 3, 4, 6, 11, 10, 7, 1, 8]
+            .into_iter()
+            .collect();
+
+        let child = PartiallyMappedCrossover::new().crossover(&mut rng, &parent_a, &parent_b);
+
+        // positions 2..8 come from parent_a, the displaced 2 follows the
+        // mapping 4 -> 6 -> 7 to where parent_b has 7, and 11 goes to where
+        // parent_b has 8
+        let actual = child.into_iter().collect::<Vec<usize>>();
+        assert_eq!(actual, vec![9, 12, 3, 4, 5, 6, 7, 8, 10, 2, 1, 11]);
+    }
+
+    #[test]
+    fn cycle_crossover() {
+        let actual = permutation_child(CycleCrossover::new());
+        // the first and last positions form a cycle taken from parent_a,
+        // the rest is a single cycle taken from parent_b
+        assert_eq!(actual, vec![1, 6, 4, 2, 7, 5, 3, 8]);
+    }
+
+    #[test]
+    #[should_panic(expected = "parents are not permutations of each other")]
+    fn cycle_crossover_with_repeated_genes() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let parent = vec![1, 1].into_iter().collect::<Chromosome<usize>>();
+
+        CycleCrossover::new().crossover(&mut rng, &parent, &parent);
+    }
+
+    #[test]
+    fn generic_crossovers_work_on_any_gene() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let parent_a = vec![true; 8].into_iter().collect();
+        let parent_b = vec![false; 8].into_iter().collect();
+
+        let child = KPointCrossover::new(1).crossover(&mut rng, &parent_a, &parent_b);
+
+        let genes = child.into_iter().collect::<Vec<bool>>();
+        assert_eq!(
+            genes,
+            vec![true, true, false, false, false, false, false, false]
+        );
+    }
 }
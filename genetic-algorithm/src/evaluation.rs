@@ -0,0 +1,312 @@
+// Decoding and evaluation as separate steps: a `Decoder` turns a chromosome
+// into a phenotype (e.g. a neural network) and an `Evaluator` scores whole
+// batches of phenotypes. The results are cached in `Evaluated` individuals, so
+// selection never triggers another evaluation.
+// `Individual` remains the simpler alternative for cheap fitness functions.
+
+use super::{Chromosome, Gene, Individual};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+pub trait Decoder<G = f32> {
+    type Phenotype;
+
+    fn decode(&self, chromosome: &Chromosome<G>) -> Self::Phenotype;
+}
+
+pub trait Evaluator<P> {
+    fn evaluate(&self, phenotype: &P) -> Evaluation;
+
+    // Called with every phenotype of a generation at once, evaluators that
+    // batch their work (e.g. submit it to a simulator or await asynchronous
+    // jobs) override this. Returns the evaluations in phenotype order
+    fn evaluate_all(&self, phenotypes: &[P]) -> Vec<Evaluation> {
+        phenotypes
+            .iter()
+            .map(|phenotype| self.evaluate(phenotype))
+            .collect()
+    }
+}
+
+// Evaluates the phenotypes of a batch on all threads
+#[cfg(feature = "parallel")]
+pub struct ParallelEvaluator<E> {
+    evaluator: E,
+}
+
+#[cfg(feature = "parallel")]
+impl<E> ParallelEvaluator<E> {
+    pub fn new(evaluator: E) -> Self {
+        Self { evaluator }
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<P, E> Evaluator<P> for ParallelEvaluator<E>
+where
+    P: Sync,
+    E: Evaluator<P> + Sync,
+{
+    fn evaluate(&self, phenotype: &P) -> Evaluation {
+        self.evaluator.evaluate(phenotype)
+    }
+
+    fn evaluate_all(&self, phenotypes: &[P]) -> Vec<Evaluation> {
+        phenotypes
+            .par_iter()
+            .map(|phenotype| self.evaluator.evaluate(phenotype))
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct Evaluation {
+    fitness: f32,
+    // Defaults to the fitness alone, see `Individual::objectives`
+    objectives: Vec<f32>,
+    constraint_violation: f32,
+}
+
+impl Evaluation {
+    pub fn new(fitness: f32) -> Self {
+        Self {
+            fitness,
+            objectives: vec![fitness],
+            constraint_violation: 0.0,
+        }
+    }
+
+    pub fn with_objectives(mut self, objectives: Vec<f32>) -> Self {
+        self.objectives = objectives;
+        self
+    }
+
+    pub fn with_constraint_violation(mut self, constraint_violation: f32) -> Self {
+        self.constraint_violation = constraint_violation;
+        self
+    }
+
+    pub fn fitness(&self) -> f32 {
+        self.fitness
+    }
+
+    pub fn objectives(&self) -> &[f32] {
+        &self.objectives
+    }
+
+    pub fn constraint_violation(&self) -> f32 {
+        self.constraint_violation
+    }
+}
+
+// A chromosome along with its phenotype and evaluation. Creating it from a
+// chromosome alone would need the decoder and the evaluator, so it's evolved
+// by the `*_evaluated` methods, e.g. `GeneticAlgorithm::evolve_evaluated` or
+// `Evolution::run_evaluated`
+#[derive(Debug)]
+pub struct Evaluated<P, G = f32> {
+    chromosome: Chromosome<G>,
+    phenotype: P,
+    evaluation: Evaluation,
+}
+
+impl<P, G> Evaluated<P, G> {
+    pub fn new(chromosome: Chromosome<G>, phenotype: P, evaluation: Evaluation) -> Self {
+        Self {
+            chromosome,
+            phenotype,
+            evaluation,
+        }
+    }
+
+    // Decodes every chromosome and evaluates the phenotypes as a single batch
+    pub fn evaluate_all<D, E>(
+        decoder: &D,
+        evaluator: &E,
+        chromosomes: Vec<Chromosome<G>>,
+    ) -> Vec<Self>
+    where
+        D: Decoder<G, Phenotype = P>,
+        E: Evaluator<P>,
+    {
+        let phenotypes = chromosomes
+            .iter()
+            .map(|chromosome| decoder.decode(chromosome))
+            .collect::<Vec<P>>();
+        let evaluations = evaluator.evaluate_all(&phenotypes);
+        assert_eq!(
+            evaluations.len(),
+            phenotypes.len(),
+            "got a different number of evaluations and phenotypes"
+        );
+
+        chromosomes
+            .into_iter()
+            .zip(phenotypes)
+            .zip(evaluations)
+            .map(|((chromosome, phenotype), evaluation)| {
+                Self::new(chromosome, phenotype, evaluation)
+            })
+            .collect()
+    }
+
+    pub fn phenotype(&self) -> &P {
+        &self.phenotype
+    }
+
+    pub fn evaluation(&self) -> &Evaluation {
+        &self.evaluation
+    }
+
+    pub fn into_phenotype(self) -> P {
+        self.phenotype
+    }
+
+    // Copy of the individual with the phenotype decoded again instead of
+    // cloned, the evaluation is kept
+    pub(crate) fn decoded_copy<D>(&self, decoder: &D) -> Self
+    where
+        G: Clone,
+        D: Decoder<G, Phenotype = P>,
+    {
+        Self::new(
+            self.chromosome.clone(),
+            decoder.decode(&self.chromosome),
+            self.evaluation.clone(),
+        )
+    }
+}
+
+impl<P, G> Individual for Evaluated<P, G>
+where
+    G: Gene,
+{
+    type Gene = G;
+
+    fn fitness(&self) -> f32 {
+        self.evaluation.fitness
+    }
+
+    fn constraint_violation(&self) -> f32 {
+        self.evaluation.constraint_violation
+    }
+
+    fn objectives(&self) -> Vec<f32> {
+        self.evaluation.objectives.clone()
+    }
+
+    fn chromosome(&self) -> &Chromosome<G> {
+        &self.chromosome
+    }
+
+    fn create(_chromosome: Chromosome<G>) -> Self {
+        panic!("evaluated individuals are created by the `*_evaluated` methods")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        GeneticAlgorithm, Identity, RouletteWheelSelection, SteadyState, Sum, TestIndividual,
+        UniformCrossover, UniformMutation, WorstReplacement,
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng as Cc8;
+
+    fn chromosomes() -> Vec<Chromosome> {
+        (0..8)
+            .map(|n| vec![n as f32, 1.0, (n % 3) as f32].into_iter().collect())
+            .collect()
+    }
+
+    fn algorithm() -> GeneticAlgorithm<UniformCrossover, UniformMutation, RouletteWheelSelection> {
+        GeneticAlgorithm::new(
+            UniformCrossover::new(),
+            UniformMutation::new(0.5, 0.5),
+            RouletteWheelSelection::new(),
+        )
+        .with_elitism(2)
+    }
+
+    #[test]
+    fn evaluates_every_child_once_per_generation() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let mut ga = algorithm();
+        let evaluator = Sum::default();
+
+        let mut population = Evaluated::evaluate_all(&Identity, &evaluator, chromosomes());
+        for _ in 0..5 {
+            population = ga
+                .evolve_evaluated(&mut rng, &population, &Identity, &evaluator)
+                .0;
+        }
+
+        // the elites are never evaluated again
+        assert_eq!(*evaluator.batches.lock().unwrap(), vec![8, 6, 6, 6, 6, 6]);
+        for individual in population.iter() {
+            assert_eq!(
+                individual.phenotype(),
+                &Identity.decode(individual.chromosome())
+            );
+            assert_eq!(
+                individual.fitness(),
+                individual.chromosome().iter().sum::<f32>()
+            );
+        }
+    }
+
+    #[test]
+    fn evaluated_evolution_matches_individuals() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let mut ga = algorithm();
+        let mut evaluated = Evaluated::evaluate_all(&Identity, &Sum::default(), chromosomes());
+        for _ in 0..10 {
+            evaluated = ga
+                .evolve_evaluated(&mut rng, &evaluated, &Identity, &Sum::default())
+                .0;
+        }
+
+        let mut rng = Cc8::from_seed(Default::default());
+        let mut ga = algorithm();
+        let mut individuals = chromosomes()
+            .into_iter()
+            .map(TestIndividual::create)
+            .collect::<Vec<TestIndividual>>();
+        for _ in 0..10 {
+            individuals = ga.evolve(&mut rng, &individuals).0;
+        }
+
+        let expected = individuals
+            .into_iter()
+            .map(|individual| individual.chromosome().clone())
+            .collect::<Vec<Chromosome>>();
+        let actual = evaluated
+            .into_iter()
+            .map(|individual| individual.chromosome().clone())
+            .collect::<Vec<Chromosome>>();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "evaluated individuals are created by the `*_evaluated` methods")]
+    fn evaluated_individuals_are_not_created_from_chromosomes() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let population = Evaluated::evaluate_all(&Identity, &Sum::default(), chromosomes());
+        let mut steady_state = SteadyState::new(algorithm(), WorstReplacement::new(), population);
+
+        steady_state.step(&mut rng);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_evaluator() {
+        let phenotypes = (0..16).map(|n| vec![n as f32; 3]).collect::<Vec<_>>();
+
+        let expected = Sum::default().evaluate_all(&phenotypes);
+        let actual = ParallelEvaluator::new(Sum::default()).evaluate_all(&phenotypes);
+        assert_eq!(actual, expected);
+    }
+}
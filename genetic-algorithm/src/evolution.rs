@@ -0,0 +1,398 @@
+use super::{
+    CrossoverMethod, Decoder, Evaluated, Evaluator, Gene, GeneticAlgorithm, Individual,
+    MutationMethod, SelectionMethod, Statistics,
+};
+use rand::RngCore;
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StopReason {
+    MaxGenerations,
+    TargetFitness,
+    Stagnation,
+    TimeBudget,
+    // An observer called `Evolution::request_stop`
+    Requested,
+}
+
+type GenerationObserver<'a, C, M, S, I, R> =
+    Box<dyn FnMut(&Evolution<'a, C, M, S, I, R>, &Statistics) + 'a>;
+type NewBestObserver<'a, C, M, S, I, R> = Box<dyn FnMut(&Evolution<'a, C, M, S, I, R>, &I) + 'a>;
+
+// Runs a `GeneticAlgorithm` generation after generation until one of the
+// stopping conditions is met, notifying the observers along the way
+pub struct Evolution<'a, C, M, S, I, R> {
+    algorithm: GeneticAlgorithm<C, M, S>,
+    population: Vec<I>,
+    rng: R,
+    // Stops once the algorithm has evolved this many generations in total
+    max_generations: Option<usize>,
+    // Stops once an individual at least this fit appears
+    target_fitness: Option<f32>,
+    // Stops after this many generations without a new best fitness
+    stagnation_limit: Option<usize>,
+    // Stops once a run has taken this long, checked between generations
+    time_budget: Option<Duration>,
+    best_fitness: f32,
+    stagnant_generations: usize,
+    // Set by observers that want the run to end, e.g. after an error
+    stop_requested: Cell<bool>,
+    on_generation: Vec<GenerationObserver<'a, C, M, S, I, R>>,
+    on_new_best: Vec<NewBestObserver<'a, C, M, S, I, R>>,
+}
+
+impl<'a, C, M, S, I, R> Evolution<'a, C, M, S, I, R>
+where
+    C: CrossoverMethod<I::Gene>,
+    M: MutationMethod<I::Gene>,
+    S: SelectionMethod,
+    I: Individual,
+    R: RngCore,
+{
+    pub fn new(algorithm: GeneticAlgorithm<C, M, S>, population: Vec<I>, rng: R) -> Self {
+        assert!(!population.is_empty(), "got an empty population");
+        let best_fitness = population
+            .iter()
+            .map(Individual::fitness)
+            .fold(f32::MIN, f32::max);
+        Self {
+            algorithm,
+            population,
+            rng,
+            max_generations: None,
+            target_fitness: None,
+            stagnation_limit: None,
+            time_budget: None,
+            best_fitness,
+            stagnant_generations: 0,
+            stop_requested: Cell::new(false),
+            on_generation: Vec::new(),
+            on_new_best: Vec::new(),
+        }
+    }
+
+    pub fn with_max_generations(mut self, max_generations: usize) -> Self {
+        self.max_generations = Some(max_generations);
+        self
+    }
+
+    pub fn with_target_fitness(mut self, target_fitness: f32) -> Self {
+        self.target_fitness = Some(target_fitness);
+        self
+    }
+
+    pub fn with_stagnation_limit(mut self, stagnation_limit: usize) -> Self {
+        self.stagnation_limit = Some(stagnation_limit);
+        self
+    }
+
+    pub fn with_time_budget(mut self, time_budget: Duration) -> Self {
+        self.time_budget = Some(time_budget);
+        self
+    }
+
+    // Called after every generation with the statistics of the generation
+    // that was evolved, `population()` already holds its offspring
+    pub fn on_generation(
+        mut self,
+        observer: impl FnMut(&Evolution<'a, C, M, S, I, R>, &Statistics) + 'a,
+    ) -> Self {
+        self.on_generation.push(Box::new(observer));
+        self
+    }
+
+    // Called whenever an individual fitter than every previous one appears
+    pub fn on_new_best(
+        mut self,
+        observer: impl FnMut(&Evolution<'a, C, M, S, I, R>, &I) + 'a,
+    ) -> Self {
+        self.on_new_best.push(Box::new(observer));
+        self
+    }
+
+    pub fn algorithm(&self) -> &GeneticAlgorithm<C, M, S> {
+        &self.algorithm
+    }
+
+    pub fn population(&self) -> &[I] {
+        &self.population
+    }
+
+    pub fn rng(&self) -> &R {
+        &self.rng
+    }
+
+    pub fn generation(&self) -> usize {
+        self.algorithm.generation()
+    }
+
+    // Best fitness seen since the evolution was created
+    pub fn best_fitness(&self) -> f32 {
+        self.best_fitness
+    }
+
+    pub fn into_population(self) -> Vec<I> {
+        self.population
+    }
+
+    // Ends `run` before the next generation, observers only get a shared
+    // reference to the evolution, so this doesn't need a mutable one
+    pub fn request_stop(&self) {
+        self.stop_requested.set(true);
+    }
+
+    // Evolves until a stopping condition is met, at least one of them has to
+    // be set
+    pub fn run(&mut self) -> StopReason
+    where
+        I: Clone,
+    {
+        self.run_with(|algorithm, rng, population| algorithm.evolve(rng, population))
+    }
+
+    // Like `run`, but every generation is evolved with
+    // `GeneticAlgorithm::evolve_parallel`
+    #[cfg(feature = "parallel")]
+    pub fn run_parallel(&mut self) -> StopReason
+    where
+        C: Sync,
+        M: Sync,
+        S: Sync,
+        I: Clone + Send + Sync,
+        I::Gene: Send,
+    {
+        self.run_with(|algorithm, rng, population| algorithm.evolve_parallel(rng, population))
+    }
+
+    fn run_with<E>(&mut self, mut evolve: E) -> StopReason
+    where
+        E: FnMut(&mut GeneticAlgorithm<C, M, S>, &mut R, &[I]) -> (Vec<I>, Statistics),
+    {
+        assert!(
+            self.max_generations.is_some()
+                || self.target_fitness.is_some()
+                || self.stagnation_limit.is_some()
+                || self.time_budget.is_some(),
+            "got no stopping condition"
+        );
+
+        let start = Instant::now();
+        loop {
+            if let Some(reason) = self.stop_reason(start) {
+                return reason;
+            }
+            self.step(&mut evolve);
+        }
+    }
+
+    fn stop_reason(&self, start: Instant) -> Option<StopReason> {
+        if self.stop_requested.get() {
+            Some(StopReason::Requested)
+        } else if matches!(self.target_fitness, Some(target) if self.best_fitness >= target) {
+            Some(StopReason::TargetFitness)
+        } else if matches!(self.max_generations, Some(max) if self.generation() >= max) {
+            Some(StopReason::MaxGenerations)
+        } else if matches!(self.stagnation_limit, Some(limit) if self.stagnant_generations >= limit)
+        {
+            Some(StopReason::Stagnation)
+        } else if matches!(self.time_budget, Some(budget) if start.elapsed() >= budget) {
+            Some(StopReason::TimeBudget)
+        } else {
+            None
+        }
+    }
+
+    fn step<E>(&mut self, evolve: &mut E)
+    where
+        E: FnMut(&mut GeneticAlgorithm<C, M, S>, &mut R, &[I]) -> (Vec<I>, Statistics),
+    {
+        let (next, statistics) = evolve(&mut self.algorithm, &mut self.rng, &self.population);
+        self.population = next;
+
+        // observers are moved out so that they can look at the evolution
+        let mut observers = std::mem::take(&mut self.on_generation);
+        for observer in observers.iter_mut() {
+            observer(self, &statistics);
+        }
+        self.on_generation = observers;
+
+        let (index, fitness) = self
+            .population
+            .iter()
+            .map(Individual::fitness)
+            .enumerate()
+            .fold((0, f32::MIN), |best, (index, fitness)| {
+                if fitness > best.1 {
+                    (index, fitness)
+                } else {
+                    best
+                }
+            });
+        if fitness > self.best_fitness {
+            self.best_fitness = fitness;
+            self.stagnant_generations = 0;
+
+            let mut observers = std::mem::take(&mut self.on_new_best);
+            for observer in observers.iter_mut() {
+                observer(self, &self.population[index]);
+            }
+            self.on_new_best = observers;
+        } else {
+            self.stagnant_generations += 1;
+        }
+    }
+}
+
+impl<'a, C, M, S, P, G, R> Evolution<'a, C, M, S, Evaluated<P, G>, R>
+where
+    C: CrossoverMethod<G>,
+    M: MutationMethod<G>,
+    S: SelectionMethod,
+    G: Gene,
+    R: RngCore,
+{
+    // Like `run`, but every generation is evolved with
+    // `GeneticAlgorithm::evolve_evaluated`
+    pub fn run_evaluated<D, E>(&mut self, decoder: &D, evaluator: &E) -> StopReason
+    where
+        D: Decoder<G, Phenotype = P>,
+        E: Evaluator<P>,
+    {
+        self.run_with(|algorithm, rng, population| {
+            algorithm.evolve_evaluated(rng, population, decoder, evaluator)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        Identity, RouletteWheelSelection, Sum, TestIndividual, UniformCrossover, UniformMutation,
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng as Cc8;
+
+    type Algorithm = GeneticAlgorithm<UniformCrossover, UniformMutation, RouletteWheelSelection>;
+
+    fn algorithm(chance: f32) -> Algorithm {
+        GeneticAlgorithm::new(
+            UniformCrossover::new(),
+            UniformMutation::new(chance, 0.5),
+            RouletteWheelSelection::new(),
+        )
+        .with_elitism(1)
+    }
+
+    fn population() -> Vec<TestIndividual> {
+        (0..8)
+            .map(|n| TestIndividual::create(vec![n as f32, 1.0].into_iter().collect()))
+            .collect()
+    }
+
+    fn rng() -> Cc8 {
+        Cc8::from_seed(Default::default())
+    }
+
+    #[test]
+    fn stops_after_max_generations() {
+        let mut generations = Vec::new();
+        let mut evolution = Evolution::new(algorithm(0.5), population(), rng())
+            .with_max_generations(5)
+            .on_generation(|evolution, _| generations.push(evolution.generation()));
+
+        assert_eq!(evolution.run(), StopReason::MaxGenerations);
+        assert_eq!(evolution.generation(), 5);
+        drop(evolution);
+        assert_eq!(generations, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn stops_at_target_fitness() {
+        let mut bests = Vec::new();
+        let mut evolution = Evolution::new(algorithm(0.5), population(), rng())
+            .with_target_fitness(10.0)
+            .with_max_generations(1000)
+            .on_new_best(|_, best| bests.push(best.fitness()));
+
+        assert_eq!(evolution.run(), StopReason::TargetFitness);
+        assert!(evolution.best_fitness() >= 10.0);
+        let best_fitness = evolution.best_fitness();
+        drop(evolution);
+
+        assert!(bests.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(*bests.last().unwrap(), best_fitness);
+    }
+
+    #[test]
+    fn stops_on_stagnation() {
+        // without mutation nothing fitter than the initial best can appear
+        let mut new_bests = 0;
+        let mut evolution = Evolution::new(algorithm(0.0), population(), rng())
+            .with_stagnation_limit(3)
+            .on_new_best(|_, _| new_bests += 1);
+
+        assert_eq!(evolution.run(), StopReason::Stagnation);
+        assert_eq!(evolution.generation(), 3);
+        drop(evolution);
+        assert_eq!(new_bests, 0);
+    }
+
+    #[test]
+    fn stops_when_requested() {
+        let mut evolution = Evolution::new(algorithm(0.5), population(), rng())
+            .with_max_generations(100)
+            .on_generation(|evolution, _| {
+                if evolution.generation() == 3 {
+                    evolution.request_stop();
+                }
+            });
+
+        assert_eq!(evolution.run(), StopReason::Requested);
+        assert_eq!(evolution.generation(), 3);
+    }
+
+    #[test]
+    fn stops_when_out_of_time() {
+        let mut evolution = Evolution::new(algorithm(0.5), population(), rng())
+            .with_time_budget(Duration::from_secs(0));
+
+        assert_eq!(evolution.run(), StopReason::TimeBudget);
+        assert_eq!(evolution.generation(), 0);
+        assert_eq!(evolution.into_population().len(), 8);
+    }
+
+    #[test]
+    fn evaluated_run_matches_individuals() {
+        let evaluator = Sum::default();
+        let chromosomes = population()
+            .iter()
+            .map(|individual| individual.chromosome().clone())
+            .collect();
+        let initial = Evaluated::evaluate_all(&Identity, &evaluator, chromosomes);
+        let mut evaluated = Evolution::new(algorithm(0.5), initial, rng()).with_max_generations(5);
+        let mut individuals =
+            Evolution::new(algorithm(0.5), population(), rng()).with_max_generations(5);
+
+        assert_eq!(
+            evaluated.run_evaluated(&Identity, &evaluator),
+            StopReason::MaxGenerations
+        );
+        assert_eq!(individuals.run(), StopReason::MaxGenerations);
+        // the elite keeps its evaluation
+        assert_eq!(*evaluator.batches.lock().unwrap(), vec![8, 7, 7, 7, 7, 7]);
+        assert_eq!(evaluated.best_fitness(), individuals.best_fitness());
+        let evaluated = evaluated
+            .into_population()
+            .into_iter()
+            .map(|individual| individual.chromosome().clone())
+            .collect::<Vec<_>>();
+        let individuals = individuals
+            .into_population()
+            .into_iter()
+            .map(|individual| individual.chromosome().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(evaluated, individuals);
+    }
+}
@@ -0,0 +1,84 @@
+// Anything a chromosome can be made of, e.g. real numbers, bits, integers or
+// the items of a permutation
+pub trait Gene: Clone {
+    // Numeric view of the gene used to measure diversity and the distance of
+    // chromosomes
+    fn value(&self) -> f32;
+
+    // Inverse of `value`, used to write repaired values back into a gene
+    fn from_value(value: f32) -> Self;
+}
+
+impl Gene for f32 {
+    fn value(&self) -> f32 {
+        *self
+    }
+
+    fn from_value(value: f32) -> Self {
+        value
+    }
+}
+
+impl Gene for f64 {
+    fn value(&self) -> f32 {
+        *self as f32
+    }
+
+    fn from_value(value: f32) -> Self {
+        value as f64
+    }
+}
+
+impl Gene for bool {
+    fn value(&self) -> f32 {
+        if *self {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    fn from_value(value: f32) -> Self {
+        value >= 0.5
+    }
+}
+
+impl Gene for i32 {
+    fn value(&self) -> f32 {
+        *self as f32
+    }
+
+    fn from_value(value: f32) -> Self {
+        value.round() as i32
+    }
+}
+
+impl Gene for i64 {
+    fn value(&self) -> f32 {
+        *self as f32
+    }
+
+    fn from_value(value: f32) -> Self {
+        value.round() as i64
+    }
+}
+
+impl Gene for u32 {
+    fn value(&self) -> f32 {
+        *self as f32
+    }
+
+    fn from_value(value: f32) -> Self {
+        value.round() as u32
+    }
+}
+
+impl Gene for usize {
+    fn value(&self) -> f32 {
+        *self as f32
+    }
+
+    fn from_value(value: f32) -> Self {
+        value.round() as usize
+    }
+}
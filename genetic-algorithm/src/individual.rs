@@ -1,7 +1,26 @@
-use super::Chromosome;
+use super::{Chromosome, Gene};
 
 pub trait Individual {
+    type Gene: Gene;
+
     fn fitness(&self) -> f32;
-    fn chromosome(&self) -> &Chromosome;
-    fn create(chromosome: Chromosome) -> Self;
+
+    // How much the individual violates the constraints of the problem, 0 for
+    // feasible individuals, see `Penalty` and `FeasibilityRules`
+    fn constraint_violation(&self) -> f32 {
+        0.0
+    }
+
+    fn chromosome(&self) -> &Chromosome<Self::Gene>;
+
+    // Individuals that need a decoder and an evaluator to be created, like
+    // `Evaluated`, can't implement this and are evolved by the `*_evaluated`
+    // methods of the drivers instead
+    fn create(chromosome: Chromosome<Self::Gene>) -> Self;
+
+    // Objectives of multi-objective optimization, every one of them is
+    // maximized like the fitness
+    fn objectives(&self) -> Vec<f32> {
+        vec![self.fitness()]
+    }
 }
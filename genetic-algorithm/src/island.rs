@@ -0,0 +1,466 @@
+use super::{
+    fittest, CrossoverMethod, Decoder, Evaluated, Evaluator, Gene, GeneticAlgorithm, Individual,
+    MutationMethod, SelectionMethod, Statistics,
+};
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore};
+use std::cmp::Ordering;
+
+#[cfg(feature = "parallel")]
+use super::seeds;
+#[cfg(feature = "parallel")]
+use rand::SeedableRng;
+#[cfg(feature = "parallel")]
+use rand_chacha::ChaCha8Rng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MigrationTopology {
+    // Island i sends its migrants to island i + 1, the last one to the first
+    Ring,
+    // Every island sends its migrants to every other island
+    FullyConnected,
+    // Every island sends its migrants to a randomly chosen other island
+    Random,
+}
+
+// Sub-populations that evolve independently and periodically exchange their
+// best individuals, which keeps them from converging prematurely.
+// Every island has its own operators, but of the same types: boxed crossover
+// and mutation methods (e.g. `Box<dyn CrossoverMethod>`) let islands use
+// different ones. Selection methods can't be boxed, so every island uses the
+// same kind of selection, possibly with different parameters
+pub struct IslandModel<C, M, S, I> {
+    islands: Vec<(GeneticAlgorithm<C, M, S>, Vec<I>)>,
+    topology: MigrationTopology,
+    // Migration happens after every `interval` generations
+    interval: usize,
+    // Number of the best individuals that leave an island when migrating,
+    // they replace the worst individuals of the destination
+    migrant_count: usize,
+    generation: usize,
+    // Generations left until the next migration
+    until_migration: usize,
+}
+
+impl<C, M, S, I> IslandModel<C, M, S, I>
+where
+    C: CrossoverMethod<I::Gene>,
+    M: MutationMethod<I::Gene>,
+    S: SelectionMethod,
+    I: Individual,
+{
+    pub fn new(
+        islands: Vec<(GeneticAlgorithm<C, M, S>, Vec<I>)>,
+        topology: MigrationTopology,
+    ) -> Self {
+        assert!(!islands.is_empty(), "got no islands");
+        assert!(
+            islands.iter().all(|(_, population)| !population.is_empty()),
+            "got an empty island"
+        );
+        Self {
+            islands,
+            topology,
+            interval: 10,
+            migrant_count: 1,
+            generation: 0,
+            until_migration: 10,
+        }
+    }
+
+    pub fn with_migration_interval(mut self, interval: usize) -> Self {
+        assert!(interval > 0);
+        self.interval = interval;
+        self.until_migration = interval;
+        self
+    }
+
+    pub fn with_migrant_count(mut self, migrant_count: usize) -> Self {
+        self.migrant_count = migrant_count;
+        self
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn island_count(&self) -> usize {
+        self.islands.len()
+    }
+
+    pub fn population(&self, island: usize) -> &[I] {
+        &self.islands[island].1
+    }
+
+    // The fittest individual across all islands
+    pub fn best(&self) -> &I {
+        self.islands
+            .iter()
+            .flat_map(|(_, population)| population.iter())
+            .max_by(|a, b| {
+                a.fitness()
+                    .partial_cmp(&b.fitness())
+                    .unwrap_or(Ordering::Equal)
+            })
+            .unwrap()
+    }
+
+    // Evolves every island by one generation and migrates if it's time,
+    // returns the statistics of the evolved populations in island order
+    pub fn evolve(&mut self, rng: &mut dyn RngCore) -> Vec<Statistics>
+    where
+        I: Clone,
+    {
+        let statistics = self
+            .islands
+            .iter_mut()
+            .map(|(algorithm, population)| {
+                let (next, statistics) = algorithm.evolve(rng, population);
+                *population = next;
+                statistics
+            })
+            .collect();
+        self.advance(rng, I::clone);
+        statistics
+    }
+
+    // Like `evolve`, but the islands evolve on all threads. Every island gets
+    // its own random stream, so the outcome doesn't depend on the thread
+    // count, but differs from `evolve`'s
+    #[cfg(feature = "parallel")]
+    pub fn evolve_parallel(&mut self, rng: &mut dyn RngCore) -> Vec<Statistics>
+    where
+        C: Send,
+        M: Send,
+        S: Send,
+        I: Clone + Send,
+    {
+        let seeds = seeds(rng, self.islands.len());
+        let statistics = self
+            .islands
+            .par_iter_mut()
+            .zip(seeds)
+            .map(|((algorithm, population), seed)| evolve_island(algorithm, population, seed))
+            .collect();
+        self.advance(rng, I::clone);
+        statistics
+    }
+
+    // `copy` puts a migrant into its destination, the source keeps it
+    fn advance<F>(&mut self, rng: &mut dyn RngCore, copy: F)
+    where
+        F: Fn(&I) -> I,
+    {
+        self.generation += 1;
+        self.until_migration -= 1;
+        if self.until_migration == 0 {
+            self.migrate(rng, copy);
+            self.until_migration = self.interval;
+        }
+    }
+
+    fn migrate<F>(&mut self, rng: &mut dyn RngCore, copy: F)
+    where
+        F: Fn(&I) -> I,
+    {
+        let count = self.islands.len();
+        if count < 2 || self.migrant_count == 0 {
+            return;
+        }
+
+        let mut immigrants = (0..count).map(|_| Vec::new()).collect::<Vec<Vec<I>>>();
+        for (source, (_, population)) in self.islands.iter().enumerate() {
+            let migrants = fittest(population, self.migrant_count);
+
+            let destinations = match self.topology {
+                MigrationTopology::Ring => vec![(source + 1) % count],
+                MigrationTopology::FullyConnected => {
+                    (0..count).filter(|&island| island != source).collect()
+                }
+                MigrationTopology::Random => {
+                    // skips the source island itself
+                    let island = rng.gen_range(0..count - 1);
+                    vec![if island >= source { island + 1 } else { island }]
+                }
+            };
+            for destination in destinations {
+                immigrants[destination].extend(migrants.iter().map(|&migrant| copy(migrant)));
+            }
+        }
+
+        for ((_, population), mut immigrants) in self.islands.iter_mut().zip(immigrants) {
+            // with many sources there can be more immigrants than places
+            immigrants.shuffle(rng);
+            immigrants.truncate(population.len());
+
+            let mut worst = (0..population.len()).collect::<Vec<usize>>();
+            worst.sort_by(|&a, &b| {
+                population[a]
+                    .fitness()
+                    .partial_cmp(&population[b].fitness())
+                    .unwrap_or(Ordering::Equal)
+            });
+            for (index, immigrant) in worst.into_iter().zip(immigrants) {
+                population[index] = immigrant;
+            }
+        }
+    }
+}
+
+impl<C, M, S, P, G> IslandModel<C, M, S, Evaluated<P, G>>
+where
+    C: CrossoverMethod<G>,
+    M: MutationMethod<G>,
+    S: SelectionMethod,
+    G: Gene,
+{
+    // Like `evolve`, but the children of every island are decoded and
+    // evaluated as one batch, and migrants keep their evaluation
+    pub fn evolve_evaluated<D, E>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        decoder: &D,
+        evaluator: &E,
+    ) -> Vec<Statistics>
+    where
+        D: Decoder<G, Phenotype = P>,
+        E: Evaluator<P>,
+    {
+        let statistics = self
+            .islands
+            .iter_mut()
+            .map(|(algorithm, population)| {
+                let (next, statistics) =
+                    algorithm.evolve_evaluated(rng, population, decoder, evaluator);
+                *population = next;
+                statistics
+            })
+            .collect();
+        self.advance(rng, |migrant| migrant.decoded_copy(decoder));
+        statistics
+    }
+}
+
+#[cfg(feature = "parallel")]
+fn evolve_island<C, M, S, I>(
+    algorithm: &mut GeneticAlgorithm<C, M, S>,
+    population: &mut Vec<I>,
+    seed: u64,
+) -> Statistics
+where
+    C: CrossoverMethod<I::Gene>,
+    M: MutationMethod<I::Gene>,
+    S: SelectionMethod,
+    I: Individual + Clone,
+{
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let (next, statistics) = algorithm.evolve(&mut rng, population);
+    *population = next;
+    statistics
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        individual, Identity, Individual, NormalMutation, RouletteWheelSelection,
+        SinglePointCrossover, Sum, TestIndividual, UniformCrossover, UniformMutation,
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng as Cc8;
+
+    type Model =
+        IslandModel<UniformCrossover, UniformMutation, RouletteWheelSelection, TestIndividual>;
+
+    // every island is converged, so only migration can change the populations
+    fn model(topology: MigrationTopology) -> Model {
+        let islands = (1..=3)
+            .map(|n| {
+                let algorithm = GeneticAlgorithm::new(
+                    UniformCrossover::new(),
+                    UniformMutation::new(0.0, 0.0),
+                    RouletteWheelSelection::new(),
+                );
+                let population = (0..4).map(|_| individual(&[n as f32, n as f32])).collect();
+                (algorithm, population)
+            })
+            .collect();
+        IslandModel::new(islands, topology).with_migration_interval(2)
+    }
+
+    fn fitness(model: &Model, island: usize) -> Vec<f32> {
+        let mut fitness = model
+            .population(island)
+            .iter()
+            .map(Individual::fitness)
+            .collect::<Vec<f32>>();
+        fitness.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        fitness
+    }
+
+    #[test]
+    fn ring_migration() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let mut model = model(MigrationTopology::Ring);
+
+        model.evolve(&mut rng);
+        assert_eq!(fitness(&model, 0), vec![2.0, 2.0, 2.0, 2.0]);
+
+        let statistics = model.evolve(&mut rng);
+        assert_eq!(statistics.len(), 3);
+        assert_eq!(model.generation(), 2);
+        assert_eq!(fitness(&model, 0), vec![2.0, 2.0, 2.0, 6.0]);
+        assert_eq!(fitness(&model, 1), vec![2.0, 4.0, 4.0, 4.0]);
+        assert_eq!(fitness(&model, 2), vec![4.0, 6.0, 6.0, 6.0]);
+    }
+
+    #[test]
+    fn islands_with_different_operators() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let operators: Vec<(Box<dyn CrossoverMethod>, Box<dyn MutationMethod>)> = vec![
+            (
+                Box::new(UniformCrossover::new()),
+                Box::new(UniformMutation::new(0.0, 0.0)),
+            ),
+            (
+                Box::new(SinglePointCrossover::new()),
+                Box::new(NormalMutation::new(1.0, 0.5)),
+            ),
+        ];
+        let islands = operators
+            .into_iter()
+            .map(|(crossover, mutation)| {
+                let algorithm =
+                    GeneticAlgorithm::new(crossover, mutation, RouletteWheelSelection::new());
+                let population = (0..4).map(|_| individual(&[1.0, 1.0])).collect();
+                (algorithm, population)
+            })
+            .collect();
+        let mut model = IslandModel::new(islands, MigrationTopology::Ring);
+
+        model.evolve(&mut rng);
+
+        // only the second island mutates its offspring
+        assert!(model
+            .population(0)
+            .iter()
+            .all(|individual| individual.fitness() == 2.0));
+        assert!(model
+            .population(1)
+            .iter()
+            .all(|individual| individual.fitness() != 2.0));
+    }
+
+    #[test]
+    fn fully_connected_migration() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let mut model = model(MigrationTopology::FullyConnected).with_migrant_count(2);
+
+        model.evolve(&mut rng);
+        model.evolve(&mut rng);
+
+        assert_eq!(fitness(&model, 0), vec![4.0, 4.0, 6.0, 6.0]);
+        assert_eq!(fitness(&model, 1), vec![2.0, 2.0, 6.0, 6.0]);
+        assert_eq!(fitness(&model, 2), vec![2.0, 2.0, 4.0, 4.0]);
+        assert_eq!(model.best().fitness(), 6.0);
+    }
+
+    #[test]
+    fn evaluated_migration() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let evaluator = Sum::default();
+        let islands = (1..=3)
+            .map(|n| {
+                let algorithm = GeneticAlgorithm::new(
+                    UniformCrossover::new(),
+                    UniformMutation::new(0.0, 0.0),
+                    RouletteWheelSelection::new(),
+                );
+                let chromosomes = (0..4)
+                    .map(|_| individual(&[n as f32, n as f32]).chromosome().clone())
+                    .collect();
+                let population = Evaluated::evaluate_all(&Identity, &evaluator, chromosomes);
+                (algorithm, population)
+            })
+            .collect();
+        let mut model =
+            IslandModel::new(islands, MigrationTopology::Ring).with_migration_interval(2);
+
+        model.evolve_evaluated(&mut rng, &Identity, &evaluator);
+        model.evolve_evaluated(&mut rng, &Identity, &evaluator);
+
+        // only the children are evaluated, the migrants keep their evaluation
+        assert_eq!(*evaluator.batches.lock().unwrap(), vec![4; 9]);
+        let fitness = |island: usize| {
+            let mut fitness = model
+                .population(island)
+                .iter()
+                .map(Individual::fitness)
+                .collect::<Vec<f32>>();
+            fitness.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            fitness
+        };
+        assert_eq!(fitness(0), vec![2.0, 2.0, 2.0, 6.0]);
+        assert_eq!(fitness(1), vec![2.0, 4.0, 4.0, 4.0]);
+        assert_eq!(fitness(2), vec![4.0, 6.0, 6.0, 6.0]);
+    }
+
+    #[test]
+    fn random_migration_never_targets_the_source() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let mut model = model(MigrationTopology::Random).with_migration_interval(1);
+
+        model.evolve(&mut rng);
+
+        // every island sends one migrant to one of the other two islands
+        assert_eq!(model.island_count(), 3);
+        let total_received = (0..3)
+            .map(|island| {
+                let own = 2.0 * (island + 1) as f32;
+                fitness(&model, island)
+                    .into_iter()
+                    .filter(|&fitness| fitness != own)
+                    .count()
+            })
+            .sum::<usize>();
+        assert_eq!(total_received, 3);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_evolution_is_deterministic() {
+        let run = |threads: usize| {
+            let islands = (0..4)
+                .map(|n| {
+                    let algorithm = GeneticAlgorithm::new(
+                        UniformCrossover::new(),
+                        UniformMutation::new(0.5, 0.5),
+                        RouletteWheelSelection::new(),
+                    );
+                    let population = (0..8).map(|m| individual(&[n as f32, m as f32])).collect();
+                    (algorithm, population)
+                })
+                .collect();
+            let mut model: Model = IslandModel::new(islands, MigrationTopology::Random);
+
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            pool.install(|| {
+                let mut rng = Cc8::from_seed(Default::default());
+                for _ in 0..20 {
+                    model.evolve_parallel(&mut rng);
+                }
+            });
+            (0..4)
+                .flat_map(|island| model.population(island))
+                .map(|individual| individual.chromosome().clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(run(1), run(4));
+    }
+}
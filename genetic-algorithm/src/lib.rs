@@ -1,61 +1,370 @@
 #![feature(min_type_alias_impl_trait)]
 
+#[cfg(feature = "checkpoint")]
+mod checkpoint;
 mod chromosome;
+mod constraint;
 mod crossover;
+mod evaluation;
+mod evolution;
+mod gene;
 mod individual;
+mod island;
 mod mutation;
+mod niching;
+mod nsga2;
+mod schedule;
 mod selection;
+mod statistics;
+mod steady_state;
 
-use chromosome::Chromosome;
-use individual::Individual;
-
-pub use crossover::{CrossoverMethod, UniformCrossover};
-pub use mutation::{GaussianMutation, MutationMethod};
-pub use selection::{RouletteWheelSelection, SelectionMethod};
+#[cfg(feature = "checkpoint")]
+pub use checkpoint::{Checkpoint, CheckpointError, CHECKPOINT_VERSION};
+pub use chromosome::{euclidean_distance, Chromosome};
+pub use constraint::{Bounds, FeasibilityRules, Penalty, Repair};
+pub use crossover::{
+    ArithmeticCrossover, BlxAlphaCrossover, CrossoverMethod, CycleCrossover, KPointCrossover,
+    OrderCrossover, PartiallyMappedCrossover, SimulatedBinaryCrossover, SinglePointCrossover,
+    UniformCrossover,
+};
+#[cfg(feature = "parallel")]
+pub use evaluation::ParallelEvaluator;
+pub use evaluation::{Decoder, Evaluated, Evaluation, Evaluator};
+pub use evolution::{Evolution, StopReason};
+pub use gene::Gene;
+pub use individual::Individual;
+pub use island::{IslandModel, MigrationTopology};
+#[allow(deprecated)]
+pub use mutation::GaussianMutation;
+pub use mutation::{
+    BitFlipMutation, CauchyMutation, IntegerCreepMutation, InversionMutation, MutationContext,
+    MutationMethod, NormalMutation, OneFifthRuleMutation, PolynomialMutation,
+    ScheduledGaussianMutation, SelfAdaptiveMutation, SwapMutation, UniformMutation,
+};
+pub use niching::{Clearing, Distance, FitnessSharing, Speciation};
+pub use nsga2::{
+    crowding_distance, dominates, non_dominated_sort, pareto_front, CrowdedTournamentSelection,
+    Nsga2,
+};
+pub use schedule::{ExponentialDecay, LinearDecay, Schedule};
+pub use selection::{
+    RankSelection, Ranking, RouletteWheelSelection, SelectionMethod, StochasticUniversalSampling,
+    TournamentSelection,
+};
+pub use statistics::Statistics;
+pub use steady_state::{
+    InverseTournamentReplacement, OldestReplacement, RandomReplacement, ReplacementMethod,
+    SteadyState, WorstReplacement,
+};
 
 use rand::RngCore;
+use std::cmp::Ordering;
+
+#[cfg(feature = "parallel")]
+use rand::SeedableRng;
+#[cfg(feature = "parallel")]
+use rand_chacha::ChaCha8Rng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
 pub struct GeneticAlgorithm<C, M, S> {
     crossover_method: C,
     mutation_method: M,
     selection_method: S,
+    // Number of the fittest individuals copied unchanged into the next generation
+    elite_count: usize,
+    // Number of generations evolved so far
+    generation: usize,
+    // Fraction of the last generation's offspring that outperformed both of
+    // their parents, `None` before the first generation
+    success_rate: Option<f32>,
+    // Genes of every child are repaired into these bounds after mutation
+    #[cfg_attr(feature = "checkpoint", serde(default))]
+    bounds: Option<Bounds>,
 }
 
-impl<C, M, S> GeneticAlgorithm<C, M, S>
-where
-    C: CrossoverMethod,
-    M: MutationMethod,
-    S: SelectionMethod,
-{
+impl<C, M, S> GeneticAlgorithm<C, M, S> {
     pub fn new(crossover_method: C, mutation_method: M, selection_method: S) -> Self {
         Self {
             crossover_method,
             mutation_method,
             selection_method,
+            elite_count: 0,
+            generation: 0,
+            success_rate: None,
+            bounds: None,
         }
     }
 
-    pub fn evolve<I>(&self, rng: &mut dyn RngCore, population: &[I]) -> Vec<I>
+    pub fn with_elitism(mut self, elite_count: usize) -> Self {
+        self.elite_count = elite_count;
+        self
+    }
+
+    pub fn with_bounds(mut self, bounds: Bounds) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+}
+
+impl<C, M, S> GeneticAlgorithm<C, M, S>
+where
+    S: SelectionMethod,
+{
+    // Returns the next generation along with the statistics of the evolved one
+    // The elites are cloned into the next generation instead of being created
+    // again from their chromosomes
+    pub fn evolve<I>(&mut self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    where
+        I: Individual + Clone,
+        C: CrossoverMethod<I::Gene>,
+        M: MutationMethod<I::Gene>,
+    {
+        self.step(rng, population, Self::breed, |elites, children| {
+            let mut next_generation = elites.into_iter().cloned().collect::<Vec<I>>();
+            next_generation.extend(create_all(children));
+            next_generation
+        })
+    }
+
+    // Like `evolve`, but breeds and creates the offspring on all threads.
+    // Every child is bred from its own random stream, so the result doesn't
+    // depend on the number of threads, but differs from `evolve`'s
+    #[cfg(feature = "parallel")]
+    pub fn evolve_parallel<I>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+    ) -> (Vec<I>, Statistics)
+    where
+        I: Individual + Clone + Send + Sync,
+        I::Gene: Send,
+        C: CrossoverMethod<I::Gene> + Sync,
+        M: MutationMethod<I::Gene> + Sync,
+        S: Sync,
+    {
+        self.step(rng, population, Self::breed_parallel, |elites, children| {
+            let mut next_generation = elites.into_iter().cloned().collect::<Vec<I>>();
+            next_generation.extend(create_all_parallel(children));
+            next_generation
+        })
+    }
+
+    // Like `evolve`, but the children are decoded and then evaluated in a
+    // single batch, while the elites keep their evaluation
+    pub fn evolve_evaluated<P, G, D, E>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        population: &[Evaluated<P, G>],
+        decoder: &D,
+        evaluator: &E,
+    ) -> (Vec<Evaluated<P, G>>, Statistics)
+    where
+        G: Gene,
+        D: Decoder<G, Phenotype = P>,
+        E: Evaluator<P>,
+        C: CrossoverMethod<G>,
+        M: MutationMethod<G>,
+    {
+        self.step(rng, population, Self::breed, |elites, children| {
+            let mut next_generation = elites
+                .into_iter()
+                .map(|elite| elite.decoded_copy(decoder))
+                .collect::<Vec<_>>();
+            next_generation.extend(Evaluated::evaluate_all(decoder, evaluator, children));
+            next_generation
+        })
+    }
+
+    // One generation: lets `breed` select the parents and produce their
+    // children, and `create` turn the elites and the children into the next
+    // generation, elites first
+    fn step<'a, I, B, F>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        breed: B,
+        create: F,
+    ) -> (Vec<I>, Statistics)
     where
         I: Individual,
+        C: CrossoverMethod<I::Gene>,
+        M: MutationMethod<I::Gene>,
+        B: FnOnce(
+            &Self,
+            &mut dyn RngCore,
+            &'a [I],
+            usize,
+        ) -> (Vec<&'a I>, Vec<Chromosome<I::Gene>>),
+        F: FnOnce(Vec<&'a I>, Vec<Chromosome<I::Gene>>) -> Vec<I>,
     {
         assert!(!population.is_empty());
-        (0..population.len())
-            .map(|_| {
-                let parent_a = self.selection_method.select(rng, population).chromosome();
-                let parent_b = self.selection_method.select(rng, population).chromosome();
+        assert!(
+            self.elite_count <= population.len(),
+            "got more elites than individuals"
+        );
 
-                let mut child = self.crossover_method.crossover(rng, &parent_a, &parent_b);
+        self.mutation_method.adapt(&MutationContext {
+            generation: self.generation,
+            success_rate: self.success_rate,
+        });
 
-                self.mutation_method.mutate(rng, &mut child);
-                I::create(child)
+        let elites = fittest(population, self.elite_count);
+
+        let offspring_count = population.len() - self.elite_count;
+        let (parents, children) = breed(self, rng, population, offspring_count);
+        let next_generation = create(elites, children);
+
+        let offspring = &next_generation[self.elite_count..];
+        let successes = parents
+            .chunks(2)
+            .zip(offspring.iter())
+            .filter(|(parents, child)| {
+                child.fitness() > parents[0].fitness().max(parents[1].fitness())
             })
-            .collect()
+            .count();
+        self.success_rate = if offspring.is_empty() {
+            None
+        } else {
+            Some(successes as f32 / offspring.len() as f32)
+        };
+        self.generation += 1;
+
+        (next_generation, Statistics::new(population))
+    }
+
+    // Selects the parents of `count` children and breeds them, returns the
+    // parents in pairs along with the children. Unless the selection method
+    // selects in batches, both parents of a child are selected right before
+    // it's bred, which keeps the order of random draws of seeded runs
+    fn breed<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> (Vec<&'a I>, Vec<Chromosome<I::Gene>>)
+    where
+        I: Individual,
+        C: CrossoverMethod<I::Gene>,
+        M: MutationMethod<I::Gene>,
+    {
+        if self.selection_method.selects_in_batches() {
+            let parents = self
+                .selection_method
+                .select_many(rng, population, 2 * count);
+            let children = parents
+                .chunks(2)
+                .map(|parents| self.child(rng, parents[0], parents[1]))
+                .collect();
+            return (parents, children);
+        }
+
+        let mut parents = Vec::with_capacity(2 * count);
+        let children = (0..count)
+            .map(|_| {
+                let parent_a = self.selection_method.select(rng, population);
+                let parent_b = self.selection_method.select(rng, population);
+                parents.push(parent_a);
+                parents.push(parent_b);
+                self.child(rng, parent_a, parent_b)
+            })
+            .collect();
+        (parents, children)
+    }
+
+    // Every child gets its own random stream seeded from the master RNG, so
+    // the children don't depend on how the work is scheduled across threads
+    // The whole mating pool is selected up front
+    #[cfg(feature = "parallel")]
+    fn breed_parallel<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> (Vec<&'a I>, Vec<Chromosome<I::Gene>>)
+    where
+        I: Individual + Sync,
+        I::Gene: Send,
+        C: CrossoverMethod<I::Gene> + Sync,
+        M: MutationMethod<I::Gene> + Sync,
+        S: Sync,
+    {
+        let parents = self
+            .selection_method
+            .select_many(rng, population, 2 * count);
+        let children = parents
+            .par_chunks(2)
+            .zip(seeds(rng, count))
+            .map(|(parents, seed)| {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                self.child(&mut rng, parents[0], parents[1])
+            })
+            .collect();
+        (parents, children)
     }
+
+    fn child<I>(&self, rng: &mut dyn RngCore, parent_a: &I, parent_b: &I) -> Chromosome<I::Gene>
+    where
+        I: Individual,
+        C: CrossoverMethod<I::Gene>,
+        M: MutationMethod<I::Gene>,
+    {
+        let mut child =
+            self.crossover_method
+                .crossover(rng, parent_a.chromosome(), parent_b.chromosome());
+
+        self.mutation_method.mutate(rng, &mut child);
+        if let Some(bounds) = &self.bounds {
+            bounds.repair(rng, &mut child);
+        }
+        child
+    }
+}
+
+// Seeds for `count` random streams drawn from the master RNG
+#[cfg(feature = "parallel")]
+fn seeds(rng: &mut dyn RngCore, count: usize) -> Vec<u64> {
+    (0..count).map(|_| rng.next_u64()).collect()
+}
+
+fn create_all<I>(chromosomes: Vec<Chromosome<I::Gene>>) -> Vec<I>
+where
+    I: Individual,
+{
+    chromosomes.into_iter().map(I::create).collect()
+}
+
+#[cfg(feature = "parallel")]
+fn create_all_parallel<I>(chromosomes: Vec<Chromosome<I::Gene>>) -> Vec<I>
+where
+    I: Individual + Send,
+    I::Gene: Send,
+{
+    chromosomes.into_par_iter().map(I::create).collect()
+}
+
+// The `count` fittest individuals in decreasing order of fitness
+fn fittest<I>(population: &[I], count: usize) -> Vec<&I>
+where
+    I: Individual,
+{
+    let mut sorted = population.iter().collect::<Vec<&I>>();
+    sorted.sort_by(|a, b| {
+        b.fitness()
+            .partial_cmp(&a.fitness())
+            .unwrap_or(Ordering::Equal)
+    });
+    sorted.truncate(count);
+    sorted
 }
 
 #[cfg(test)]
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 enum TestIndividual {
     WithChromosome { chromosome: Chromosome },
     WithFitness { fitness: f32 },
@@ -70,6 +379,8 @@ impl TestIndividual {
 
 #[cfg(test)]
 impl Individual for TestIndividual {
+    type Gene = f32;
+
     fn fitness(&self) -> f32 {
         match self {
             Self::WithFitness { fitness } => *fitness,
@@ -77,16 +388,80 @@ impl Individual for TestIndividual {
         }
     }
 
-    fn create(chromosome: Chromosome) -> Self {
-        Self::WithChromosome { chromosome }
-    }
-
     fn chromosome(&self) -> &Chromosome {
         match self {
-            Self::WithChromosome { chromosome } => &chromosome,
+            Self::WithChromosome { chromosome } => chromosome,
             _ => panic!("not supported on this variant"),
         }
     }
+
+    fn create(chromosome: Chromosome) -> Self {
+        Self::WithChromosome { chromosome }
+    }
+}
+
+#[cfg(test)]
+fn individual(genes: &[f32]) -> TestIndividual {
+    TestIndividual::create(genes.iter().cloned().collect())
+}
+
+#[cfg(test)]
+fn population(fitness: &[f32]) -> Vec<TestIndividual> {
+    fitness
+        .iter()
+        .map(|&fitness| TestIndividual::new_with_fitness(fitness))
+        .collect()
+}
+
+// How often every fitness is picked when selecting 1000 individuals
+#[cfg(test)]
+fn histogram<S, I>(method: &S, population: &[I]) -> std::collections::BTreeMap<i32, usize>
+where
+    S: SelectionMethod,
+    I: Individual,
+{
+    use rand::SeedableRng;
+
+    let mut rng = rand_chacha::ChaCha8Rng::from_seed(Default::default());
+    let mut histogram = std::collections::BTreeMap::new();
+    for individual in method.select_many(&mut rng, population, 1000) {
+        *histogram.entry(individual.fitness() as i32).or_insert(0) += 1;
+    }
+    histogram
+}
+
+#[cfg(test)]
+struct Identity;
+
+#[cfg(test)]
+impl Decoder for Identity {
+    type Phenotype = Vec<f32>;
+
+    fn decode(&self, chromosome: &Chromosome) -> Vec<f32> {
+        chromosome.iter().copied().collect()
+    }
+}
+
+// Sums the genes like `TestIndividual` and records the size of every batch
+#[cfg(test)]
+#[derive(Default)]
+struct Sum {
+    batches: std::sync::Mutex<Vec<usize>>,
+}
+
+#[cfg(test)]
+impl Evaluator<Vec<f32>> for Sum {
+    fn evaluate(&self, phenotype: &Vec<f32>) -> Evaluation {
+        Evaluation::new(phenotype.iter().sum())
+    }
+
+    fn evaluate_all(&self, phenotypes: &[Vec<f32>]) -> Vec<Evaluation> {
+        self.batches.lock().unwrap().push(phenotypes.len());
+        phenotypes
+            .iter()
+            .map(|phenotype| self.evaluate(phenotype))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -95,17 +470,14 @@ mod test {
     use rand::SeedableRng;
     use rand_chacha::ChaCha8Rng as Cc8;
 
-    fn individual(genes: &[f32]) -> TestIndividual {
-        let chromosome = genes.iter().cloned().collect();
-        TestIndividual::create(chromosome)
-    }
-
+    // The expected population is the one of the original implementation
+    #[allow(clippy::excessive_precision)]
     #[test]
     fn evolution() {
         let mut rng = Cc8::from_seed(Default::default());
-        let ga = GeneticAlgorithm::new(
+        let mut ga = GeneticAlgorithm::new(
             UniformCrossover::new(),
-            GaussianMutation::new(0.5, 0.5),
+            UniformMutation::new(0.5, 0.5),
             RouletteWheelSelection::new(),
         );
 
@@ -117,7 +489,7 @@ mod test {
         ];
 
         for _ in 0..10 {
-            population = ga.evolve(&mut rng, &population);
+            population = ga.evolve(&mut rng, &population).0;
         }
 
         let expected_population = vec![
@@ -129,4 +501,248 @@ mod test {
 
         assert_eq!(population, expected_population);
     }
+
+    #[test]
+    fn elitism_keeps_the_best_fitness() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let mut ga = GeneticAlgorithm::new(
+            UniformCrossover::new(),
+            UniformMutation::new(1.0, 2.0),
+            RouletteWheelSelection::new(),
+        )
+        .with_elitism(1);
+
+        let mut population = vec![
+            individual(&[0.0, 0.0, 0.0]),
+            individual(&[1.0, 1.0, 1.0]),
+            individual(&[1.0, 2.0, 1.0]),
+            individual(&[1.0, 2.0, 4.0]),
+        ];
+
+        let best_fitness = |population: &[TestIndividual]| {
+            population
+                .iter()
+                .map(Individual::fitness)
+                .fold(f32::MIN, f32::max)
+        };
+
+        let mut best = best_fitness(&population);
+        for _ in 0..50 {
+            let (next, statistics) = ga.evolve(&mut rng, &population);
+
+            assert_eq!(next.len(), population.len());
+            assert_eq!(next[0], *fittest(&population, 1)[0]);
+            assert!(best_fitness(&next) >= best);
+            assert_eq!(statistics.max_fitness(), best);
+
+            best = best_fitness(&next);
+            population = next;
+        }
+        assert!(best > 7.0);
+    }
+
+    #[test]
+    fn elites_are_not_created_again() {
+        thread_local! {
+            static CREATED: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+        }
+
+        #[derive(Clone)]
+        struct Counted(Chromosome);
+
+        impl Individual for Counted {
+            type Gene = f32;
+
+            fn fitness(&self) -> f32 {
+                self.0.iter().sum()
+            }
+
+            fn chromosome(&self) -> &Chromosome {
+                &self.0
+            }
+
+            fn create(chromosome: Chromosome) -> Self {
+                CREATED.with(|created| created.set(created.get() + 1));
+                Self(chromosome)
+            }
+        }
+
+        let mut rng = Cc8::from_seed(Default::default());
+        let mut ga = GeneticAlgorithm::new(
+            UniformCrossover::new(),
+            UniformMutation::new(0.5, 0.5),
+            RouletteWheelSelection::new(),
+        )
+        .with_elitism(3);
+        let mut population = (0..8)
+            .map(|n| Counted(vec![n as f32, 1.0].into_iter().collect()))
+            .collect::<Vec<Counted>>();
+
+        for _ in 0..4 {
+            population = ga.evolve(&mut rng, &population).0;
+        }
+
+        assert_eq!(CREATED.with(|created| created.get()), 4 * 5);
+    }
+
+    #[test]
+    fn offspring_are_kept_within_bounds() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let bounds = Bounds::new(vec![(0.0, 1.0), (-1.0, 1.0), (2.0, 3.0)]);
+        let mut ga = GeneticAlgorithm::new(
+            UniformCrossover::new(),
+            NormalMutation::new(1.0, 5.0),
+            RouletteWheelSelection::new(),
+        )
+        .with_bounds(bounds.clone().with_repair(Repair::Reflect));
+
+        let mut population = vec![
+            individual(&[0.0, 0.0, 2.0]),
+            individual(&[1.0, 1.0, 3.0]),
+            individual(&[0.5, -1.0, 2.5]),
+            individual(&[1.0, 0.5, 2.0]),
+        ];
+        for _ in 0..10 {
+            population = ga.evolve(&mut rng, &population).0;
+            assert!(population
+                .iter()
+                .all(|individual| bounds.contains(individual.chromosome())));
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingMutation {
+        contexts: Vec<MutationContext>,
+    }
+
+    impl MutationMethod for RecordingMutation {
+        fn mutate(&self, _rng: &mut dyn RngCore, _child: &mut Chromosome) {}
+
+        fn adapt(&mut self, context: &MutationContext) {
+            self.contexts.push(*context);
+        }
+    }
+
+    #[test]
+    fn mutation_is_adapted_every_generation() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let mut ga = GeneticAlgorithm::new(
+            UniformCrossover::new(),
+            RecordingMutation::default(),
+            RouletteWheelSelection::new(),
+        );
+
+        let mut population = vec![individual(&[1.0, 2.0]), individual(&[1.0, 2.0])];
+        for _ in 0..3 {
+            population = ga.evolve(&mut rng, &population).0;
+        }
+
+        // identical parents without mutation never produce a fitter child
+        let expected = vec![
+            MutationContext {
+                generation: 0,
+                success_rate: None,
+            },
+            MutationContext {
+                generation: 1,
+                success_rate: Some(0.0),
+            },
+            MutationContext {
+                generation: 2,
+                success_rate: Some(0.0),
+            },
+        ];
+        assert_eq!(ga.mutation_method.contexts, expected);
+        assert_eq!(ga.generation(), 3);
+    }
+
+    // Records the number of parents asked for by every call
+    #[derive(Default)]
+    struct RecordingSelection {
+        batches: bool,
+        calls: std::cell::RefCell<Vec<usize>>,
+    }
+
+    impl SelectionMethod for RecordingSelection {
+        fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+        where
+            I: Individual,
+        {
+            self.calls.borrow_mut().push(1);
+            RouletteWheelSelection::new().select(rng, population)
+        }
+
+        fn select_many<'a, I>(
+            &self,
+            rng: &mut dyn RngCore,
+            population: &'a [I],
+            count: usize,
+        ) -> Vec<&'a I>
+        where
+            I: Individual,
+        {
+            self.calls.borrow_mut().push(count);
+            RouletteWheelSelection::new().select_many(rng, population, count)
+        }
+
+        fn selects_in_batches(&self) -> bool {
+            self.batches
+        }
+    }
+
+    #[test]
+    fn mating_pool_is_selected_in_batches_only_on_request() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let population = (1..=4)
+            .map(|n| individual(&[n as f32]))
+            .collect::<Vec<TestIndividual>>();
+
+        let mut ga = GeneticAlgorithm::new(
+            UniformCrossover::new(),
+            UniformMutation::new(0.5, 0.5),
+            RecordingSelection::default(),
+        );
+        ga.evolve(&mut rng, &population);
+        assert_eq!(*ga.selection_method.calls.borrow(), vec![1; 8]);
+
+        let mut ga = GeneticAlgorithm::new(
+            UniformCrossover::new(),
+            UniformMutation::new(0.5, 0.5),
+            RecordingSelection {
+                batches: true,
+                ..Default::default()
+            },
+        );
+        ga.evolve(&mut rng, &population);
+        assert_eq!(*ga.selection_method.calls.borrow(), vec![8]);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_evolution_is_deterministic() {
+        let run = |threads: usize| {
+            let mut ga = GeneticAlgorithm::new(
+                UniformCrossover::new(),
+                UniformMutation::new(0.5, 0.5),
+                RouletteWheelSelection::new(),
+            )
+            .with_elitism(1);
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            pool.install(|| {
+                let mut rng = Cc8::from_seed(Default::default());
+                let mut population = (0..32)
+                    .map(|n| individual(&[n as f32, 1.0, (n % 5) as f32]))
+                    .collect::<Vec<TestIndividual>>();
+                for _ in 0..10 {
+                    population = ga.evolve_parallel(&mut rng, &population).0;
+                }
+                population
+            })
+        };
+
+        assert_eq!(run(1), run(4));
+    }
 }
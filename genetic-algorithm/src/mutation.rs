@@ -1,11 +1,41 @@
-use super::Chromosome;
+use super::{Chromosome, Schedule};
+use rand::seq::index;
 use rand::{Rng, RngCore};
+use rand_distr::{Cauchy, Distribution, Normal, StandardNormal};
 
-pub trait MutationMethod {
-    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome);
+pub trait MutationMethod<G = f32> {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome<G>);
+
+    // Called by `GeneticAlgorithm` at the start of every generation, before
+    // any child is mutated
+    fn adapt(&mut self, _context: &MutationContext) {}
+}
+
+// Like boxed crossover methods, see `CrossoverMethod`
+impl<G, T> MutationMethod<G> for Box<T>
+where
+    T: MutationMethod<G> + ?Sized,
+{
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome<G>) {
+        (**self).mutate(rng, child)
+    }
+
+    fn adapt(&mut self, context: &MutationContext) {
+        (**self).adapt(context)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MutationContext {
+    // Number of generations evolved so far
+    pub generation: usize,
+    // Fraction of the last generation's offspring that outperformed both of
+    // their parents, `None` before the first generation
+    pub success_rate: Option<f32>,
 }
 
-pub struct GaussianMutation {
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct UniformMutation {
     // Probability of changing a gene
     // 0.0 = no genes will be touched
     // 1.0 = all genes will be touched
@@ -16,13 +46,13 @@ pub struct GaussianMutation {
     coeff: f32,
 }
 
-impl GaussianMutation {
+impl UniformMutation {
     pub fn new(chance: f32, coeff: f32) -> Self {
         Self { chance, coeff }
     }
 }
 
-impl MutationMethod for GaussianMutation {
+impl MutationMethod for UniformMutation {
     fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
         for gene in child.iter_mut() {
             let sign = if rng.gen_bool(0.5) { -1.0 } else { 1.0 };
@@ -33,6 +63,350 @@ impl MutationMethod for GaussianMutation {
     }
 }
 
+// The original name of `UniformMutation`, despite it the noise isn't normally
+// distributed
+#[deprecated(note = "adds uniform noise, use `UniformMutation` or `NormalMutation` instead")]
+pub type GaussianMutation = UniformMutation;
+
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct NormalMutation {
+    // Probability of changing a gene
+    chance: f32,
+    // Touched genes are perturbed by a sample of N(0, sigma^2)
+    normal: Normal<f32>,
+}
+
+impl NormalMutation {
+    pub fn new(chance: f32, sigma: f32) -> Self {
+        let normal = Normal::new(0.0, sigma).expect("got an invalid standard deviation");
+        Self { chance, normal }
+    }
+}
+
+impl MutationMethod for NormalMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        for gene in child.iter_mut() {
+            if rng.gen_bool(self.chance as _) {
+                *gene += self.normal.sample(rng);
+            }
+        }
+    }
+}
+
+// Polynomial mutation (Deb & Goyal) without variable bounds: touched genes
+// are perturbed by at most `max_perturbation`, larger distribution indices
+// concentrate the perturbations around zero
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct PolynomialMutation {
+    // Probability of changing a gene
+    chance: f32,
+    distribution_index: f32,
+    max_perturbation: f32,
+}
+
+impl PolynomialMutation {
+    pub fn new(chance: f32, distribution_index: f32, max_perturbation: f32) -> Self {
+        assert!(distribution_index >= 0.0);
+        assert!(max_perturbation >= 0.0);
+        Self {
+            chance,
+            distribution_index,
+            max_perturbation,
+        }
+    }
+}
+
+impl MutationMethod for PolynomialMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        let exponent = 1.0 / (self.distribution_index + 1.0);
+        for gene in child.iter_mut() {
+            if rng.gen_bool(self.chance as _) {
+                let u = rng.gen::<f32>();
+                let delta = if u < 0.5 {
+                    (2.0 * u).powf(exponent) - 1.0
+                } else {
+                    1.0 - (2.0 * (1.0 - u)).powf(exponent)
+                };
+                *gene += delta * self.max_perturbation;
+            }
+        }
+    }
+}
+
+// Heavy-tailed perturbations that occasionally take very long jumps
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct CauchyMutation {
+    // Probability of changing a gene
+    chance: f32,
+    // Touched genes are perturbed by a sample of Cauchy(0, scale)
+    cauchy: Cauchy<f32>,
+}
+
+impl CauchyMutation {
+    pub fn new(chance: f32, scale: f32) -> Self {
+        let cauchy = Cauchy::new(0.0, scale).expect("got an invalid scale");
+        Self { chance, cauchy }
+    }
+}
+
+impl MutationMethod for CauchyMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        for gene in child.iter_mut() {
+            if rng.gen_bool(self.chance as _) {
+                *gene += self.cauchy.sample(rng);
+            }
+        }
+    }
+}
+
+// Gaussian mutation whose standard deviation follows a schedule
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScheduledGaussianMutation<S> {
+    // Probability of changing a gene
+    chance: f32,
+    schedule: S,
+    // Standard deviation of the current generation
+    sigma: f32,
+}
+
+impl<S> ScheduledGaussianMutation<S>
+where
+    S: Schedule,
+{
+    pub fn new(chance: f32, schedule: S) -> Self {
+        let sigma = schedule.value(0);
+        Self {
+            chance,
+            schedule,
+            sigma,
+        }
+    }
+
+    pub fn sigma(&self) -> f32 {
+        self.sigma
+    }
+}
+
+impl<S> MutationMethod for ScheduledGaussianMutation<S>
+where
+    S: Schedule,
+{
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        let normal = Normal::new(0.0, self.sigma).expect("got an invalid standard deviation");
+        for gene in child.iter_mut() {
+            if rng.gen_bool(self.chance as _) {
+                *gene += normal.sample(rng);
+            }
+        }
+    }
+
+    fn adapt(&mut self, context: &MutationContext) {
+        self.sigma = self.schedule.value(context.generation);
+    }
+}
+
+// Rechenberg's 1/5th success rule: the standard deviation grows while more
+// than a fifth of the offspring outperform their parents and shrinks while
+// fewer do
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct OneFifthRuleMutation {
+    // Probability of changing a gene
+    chance: f32,
+    sigma: f32,
+    // The standard deviation is multiplied or divided by this every generation
+    factor: f32,
+}
+
+impl OneFifthRuleMutation {
+    pub fn new(chance: f32, sigma: f32, factor: f32) -> Self {
+        assert!(sigma >= 0.0);
+        assert!(factor > 0.0 && factor < 1.0);
+        Self {
+            chance,
+            sigma,
+            factor,
+        }
+    }
+
+    pub fn sigma(&self) -> f32 {
+        self.sigma
+    }
+}
+
+impl MutationMethod for OneFifthRuleMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        let normal = Normal::new(0.0, self.sigma).expect("got an invalid standard deviation");
+        for gene in child.iter_mut() {
+            if rng.gen_bool(self.chance as _) {
+                *gene += normal.sample(rng);
+            }
+        }
+    }
+
+    fn adapt(&mut self, context: &MutationContext) {
+        match context.success_rate {
+            Some(rate) if rate > 0.2 => self.sigma /= self.factor,
+            Some(rate) if rate < 0.2 => self.sigma *= self.factor,
+            _ => {}
+        }
+    }
+}
+
+// Evolution strategy style self-adaptation: the second half of the chromosome
+// holds a step size for every gene of the first half, and the step sizes are
+// mutated (log-normally) before they are used to mutate their genes, so that
+// good step sizes are inherited along with good genes
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelfAdaptiveMutation {
+    // Step sizes never shrink below this
+    min_step_size: f32,
+}
+
+impl SelfAdaptiveMutation {
+    pub fn new(min_step_size: f32) -> Self {
+        assert!(min_step_size >= 0.0);
+        Self { min_step_size }
+    }
+
+    // Appends the same initial step size for every gene
+    pub fn chromosome(genes: impl IntoIterator<Item = f32>, step_size: f32) -> Chromosome {
+        let genes = genes.into_iter().collect::<Vec<f32>>();
+        let step_sizes = vec![step_size; genes.len()];
+        genes.into_iter().chain(step_sizes).collect()
+    }
+}
+
+impl MutationMethod for SelfAdaptiveMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        let n = child.len() / 2;
+        assert_eq!(
+            2 * n,
+            child.len(),
+            "got a chromosome without a step size for every gene"
+        );
+        if n == 0 {
+            return;
+        }
+
+        // the usual learning rates recommended by Schwefel
+        let global_rate = 1.0 / (2.0 * n as f32).sqrt();
+        let local_rate = 1.0 / (2.0 * (n as f32).sqrt()).sqrt();
+
+        let global = global_rate * rng.sample::<f32, _>(StandardNormal);
+        let mut values = child.iter_mut().collect::<Vec<&mut f32>>();
+        let (genes, step_sizes) = values.split_at_mut(n);
+        for (gene, step_size) in genes.iter_mut().zip(step_sizes.iter_mut()) {
+            let local = local_rate * rng.sample::<f32, _>(StandardNormal);
+            **step_size = (**step_size * (global + local).exp()).max(self.min_step_size);
+            **gene += **step_size * rng.sample::<f32, _>(StandardNormal);
+        }
+    }
+}
+
+// Flips every bit with the given probability
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct BitFlipMutation {
+    chance: f32,
+}
+
+impl BitFlipMutation {
+    pub fn new(chance: f32) -> Self {
+        Self { chance }
+    }
+}
+
+impl MutationMethod<bool> for BitFlipMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome<bool>) {
+        for gene in child.iter_mut() {
+            if rng.gen_bool(self.chance as _) {
+                *gene = !*gene;
+            }
+        }
+    }
+}
+
+// Moves touched integer genes up or down by 1 to `max_step`
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntegerCreepMutation {
+    // Probability of changing a gene
+    chance: f32,
+    max_step: i32,
+}
+
+impl IntegerCreepMutation {
+    pub fn new(chance: f32, max_step: i32) -> Self {
+        assert!(max_step > 0);
+        Self { chance, max_step }
+    }
+}
+
+impl MutationMethod<i32> for IntegerCreepMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome<i32>) {
+        for gene in child.iter_mut() {
+            if rng.gen_bool(self.chance as _) {
+                let step = rng.gen_range(1..=self.max_step);
+                *gene += if rng.gen_bool(0.5) { step } else { -step };
+            }
+        }
+    }
+}
+
+// Swaps touched genes with a randomly chosen other gene, so permutations
+// stay permutations
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct SwapMutation {
+    // Probability of swapping a gene
+    chance: f32,
+}
+
+impl SwapMutation {
+    pub fn new(chance: f32) -> Self {
+        Self { chance }
+    }
+}
+
+impl<G> MutationMethod<G> for SwapMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome<G>) {
+        let len = child.len();
+        if len < 2 {
+            return;
+        }
+        for i in 0..len {
+            if rng.gen_bool(self.chance as _) {
+                // any position but `i`
+                let j = (i + rng.gen_range(1..len)) % len;
+                child.as_mut_slice().swap(i, j);
+            }
+        }
+    }
+}
+
+// Reverses a random segment of the chromosome, which keeps most adjacencies
+// of a permutation (e.g. of a route) intact
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct InversionMutation {
+    // Probability of inverting a segment of a chromosome
+    chance: f32,
+}
+
+impl InversionMutation {
+    pub fn new(chance: f32) -> Self {
+        Self { chance }
+    }
+}
+
+impl<G> MutationMethod<G> for InversionMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome<G>) {
+        let len = child.len();
+        if len < 2 || !rng.gen_bool(self.chance as _) {
+            return;
+        }
+        let mut cuts = index::sample(rng, len + 1, 2).into_vec();
+        cuts.sort_unstable();
+        child.as_mut_slice()[cuts[0]..cuts[1]].reverse();
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -43,7 +417,7 @@ mod test {
         let mut child = vec![1.0, 2.0, 3.0, 4.0, 5.0].into_iter().collect();
 
         let mut rng = Cc8::from_seed(Default::default());
-        GaussianMutation::new(chance, coeff).mutate(&mut rng, &mut child);
+        UniformMutation::new(chance, coeff).mutate(&mut rng, &mut child);
 
         child.into_iter().collect()
     }
@@ -119,4 +493,186 @@ mod test {
             }
         }
     }
+
+    fn mutated<M>(method: M) -> Vec<f32>
+    where
+        M: MutationMethod,
+    {
+        let mut child = vec![1.0, 2.0, 3.0, 4.0, 5.0].into_iter().collect();
+
+        let mut rng = Cc8::from_seed(Default::default());
+        method.mutate(&mut rng, &mut child);
+
+        child.into_iter().collect()
+    }
+
+    #[allow(deprecated)]
+    #[test]
+    fn gaussian_mutation_keeps_its_original_meaning() {
+        let actual = mutated(GaussianMutation::new(0.5, 0.5));
+        let expected = mutated(UniformMutation::new(0.5, 0.5));
+        approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn normal_mutation() {
+        let actual = mutated(NormalMutation::new(1.0, 0.5));
+        let expected = vec![1.6888486, 2.2026734, 2.4018655, 3.0324764, 4.664113];
+        approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+
+        let untouched = mutated(NormalMutation::new(0.0, 0.5));
+        approx::assert_relative_eq!(untouched.as_slice(), [1.0, 2.0, 3.0, 4.0, 5.0].as_ref());
+    }
+
+    #[test]
+    fn normal_mutation_is_normally_distributed() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let method = NormalMutation::new(1.0, 2.0);
+        let mut child = vec![0.0; 10_000].into_iter().collect();
+        method.mutate(&mut rng, &mut child);
+
+        let genes = child.into_iter().collect::<Vec<f32>>();
+        let n = genes.len() as f32;
+        let mean = genes.iter().sum::<f32>() / n;
+        let std_dev = (genes.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / n).sqrt();
+        let within_one_sigma = genes.iter().filter(|x| x.abs() < 2.0).count() as f32 / n;
+
+        assert!(mean.abs() < 0.05);
+        assert!((std_dev - 2.0).abs() < 0.05);
+        assert!((within_one_sigma - 0.6827).abs() < 0.01);
+    }
+
+    #[test]
+    fn polynomial_mutation() {
+        let actual = mutated(PolynomialMutation::new(1.0, 20.0, 1.0));
+        let expected = vec![0.9543049, 2.0519526, 3.0779574, 4.014417, 5.0347204];
+        approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn cauchy_mutation() {
+        let actual = mutated(CauchyMutation::new(0.5, 0.1));
+        let expected = vec![1.0, 2.0, 2.0859149, 4.1035833, 4.7834954];
+        approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    fn context(generation: usize, success_rate: Option<f32>) -> MutationContext {
+        MutationContext {
+            generation,
+            success_rate,
+        }
+    }
+
+    #[test]
+    fn scheduled_gaussian_mutation() {
+        let mut method = ScheduledGaussianMutation::new(1.0, crate::LinearDecay::new(0.5, 0.0, 10));
+        approx::assert_relative_eq!(method.sigma(), 0.5);
+
+        method.adapt(&context(5, None));
+        approx::assert_relative_eq!(method.sigma(), 0.25);
+
+        method.adapt(&context(10, None));
+        let actual = mutated(method);
+        approx::assert_relative_eq!(actual.as_slice(), [1.0, 2.0, 3.0, 4.0, 5.0].as_ref());
+    }
+
+    #[test]
+    fn one_fifth_rule_mutation() {
+        let mut method = OneFifthRuleMutation::new(1.0, 1.0, 0.5);
+
+        method.adapt(&context(0, None));
+        approx::assert_relative_eq!(method.sigma(), 1.0);
+
+        method.adapt(&context(1, Some(0.5)));
+        approx::assert_relative_eq!(method.sigma(), 2.0);
+
+        method.adapt(&context(2, Some(0.2)));
+        approx::assert_relative_eq!(method.sigma(), 2.0);
+
+        method.adapt(&context(3, Some(0.0)));
+        method.adapt(&context(4, Some(0.1)));
+        approx::assert_relative_eq!(method.sigma(), 0.5);
+    }
+
+    #[test]
+    fn self_adaptive_mutation() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let mut child = SelfAdaptiveMutation::chromosome(vec![1.0, 2.0, 3.0], 0.5);
+        SelfAdaptiveMutation::new(0.1).mutate(&mut rng, &mut child);
+
+        let actual = child.into_iter().collect::<Vec<f32>>();
+        let expected = vec![
+            -0.30511296,
+            1.7915821,
+            1.9357346,
+            1.0909863,
+            0.31025016,
+            0.49524295,
+        ];
+        approx::assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn self_adaptive_mutation_respects_the_min_step_size() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let method = SelfAdaptiveMutation::new(0.1);
+        let mut child = SelfAdaptiveMutation::chromosome(vec![0.0; 4], 0.1);
+        for _ in 0..100 {
+            method.mutate(&mut rng, &mut child);
+        }
+
+        assert!(child.iter().skip(4).all(|&step_size| step_size >= 0.1));
+    }
+
+    #[test]
+    fn bit_flip_mutation() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let mut child = vec![false; 8].into_iter().collect();
+        BitFlipMutation::new(0.5).mutate(&mut rng, &mut child);
+
+        let actual = child.into_iter().collect::<Vec<bool>>();
+        assert_eq!(
+            actual,
+            vec![false, false, true, true, true, true, true, true]
+        );
+    }
+
+    #[test]
+    fn integer_creep_mutation() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let mut child = vec![0; 8].into_iter().collect();
+        IntegerCreepMutation::new(0.5, 2).mutate(&mut rng, &mut child);
+
+        let actual = child.into_iter().collect::<Vec<i32>>();
+        assert_eq!(actual, vec![0, 0, 2, 1, 1, 2, 0, -1]);
+    }
+
+    fn mutated_permutation<M>(method: M) -> Vec<usize>
+    where
+        M: MutationMethod<usize>,
+    {
+        let mut rng = Cc8::from_seed(Default::default());
+        let mut child = (0..8).collect();
+        method.mutate(&mut rng, &mut child);
+        child.into_iter().collect()
+    }
+
+    #[test]
+    fn swap_mutation() {
+        let actual = mutated_permutation(SwapMutation::new(0.2));
+        let mut sorted = actual.clone();
+        sorted.sort_unstable();
+
+        assert_eq!(sorted, (0..8).collect::<Vec<usize>>());
+        assert_eq!(actual, vec![0, 1, 2, 5, 3, 4, 6, 7]);
+    }
+
+    #[test]
+    fn inversion_mutation() {
+        let actual = mutated_permutation(InversionMutation::new(1.0));
+        assert_eq!(actual, vec![0, 6, 5, 4, 3, 2, 1, 7]);
+
+        let untouched = mutated_permutation(InversionMutation::new(0.0));
+        assert_eq!(untouched, (0..8).collect::<Vec<usize>>());
+    }
 }
@@ -0,0 +1,530 @@
+// Niching methods keep several sub-populations (niches) alive by making
+// similar individuals compete with each other instead of with the whole
+// population. They wrap another selection method and only change the fitness
+// it sees or the part of the population it selects from.
+//
+// All of them assume non-negative fitness values.
+
+use super::{euclidean_distance, Chromosome, Individual, SelectionMethod};
+use rand::RngCore;
+use std::cmp::Ordering;
+
+// Distance of two chromosomes given by their gene values, see `Gene::value`
+pub type Distance = fn(&[f32], &[f32]) -> f32;
+
+// Fitness sharing (Goldberg & Richardson): the fitness of an individual is
+// divided by its niche count, the number of individuals around it weighted by
+// sh(d) = 1 - (d / radius)^alpha for distances smaller than `radius`
+pub struct FitnessSharing<S> {
+    selection_method: S,
+    radius: f32,
+    alpha: f32,
+    distance: Distance,
+}
+
+impl<S> FitnessSharing<S>
+where
+    S: SelectionMethod,
+{
+    pub fn new(selection_method: S, radius: f32, alpha: f32) -> Self {
+        assert!(radius > 0.0);
+        assert!(alpha > 0.0);
+        Self {
+            selection_method,
+            radius,
+            alpha,
+            distance: euclidean_distance,
+        }
+    }
+
+    pub fn with_distance(mut self, distance: Distance) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    // Shared fitness of every individual, in population order
+    pub fn shared_fitness<I>(&self, population: &[I]) -> Vec<f32>
+    where
+        I: Individual,
+    {
+        let values = values(population);
+        population
+            .iter()
+            .zip(values.iter())
+            .map(|(individual, a)| {
+                let niche_count = values
+                    .iter()
+                    .map(|b| (self.distance)(a, b))
+                    .filter(|&distance| distance < self.radius)
+                    .map(|distance| 1.0 - (distance / self.radius).powf(self.alpha))
+                    .sum::<f32>();
+                // every individual is in its own niche, so the count is at least 1
+                individual.fitness() / niche_count
+            })
+            .collect()
+    }
+}
+
+impl<S> SelectionMethod for FitnessSharing<S>
+where
+    S: SelectionMethod,
+{
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        let fitness = self.shared_fitness(population);
+        select_with_fitness(&self.selection_method, rng, population, fitness, count)
+    }
+
+    fn selects_in_batches(&self) -> bool {
+        true
+    }
+}
+
+// Clearing (Pétrowski): within every niche of the given radius only the
+// `capacity` fittest individuals keep their fitness, the rest are cleared to
+// zero
+pub struct Clearing<S> {
+    selection_method: S,
+    radius: f32,
+    capacity: usize,
+    distance: Distance,
+}
+
+impl<S> Clearing<S>
+where
+    S: SelectionMethod,
+{
+    pub fn new(selection_method: S, radius: f32, capacity: usize) -> Self {
+        assert!(radius > 0.0);
+        assert!(capacity > 0);
+        Self {
+            selection_method,
+            radius,
+            capacity,
+            distance: euclidean_distance,
+        }
+    }
+
+    pub fn with_distance(mut self, distance: Distance) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    // Fitness of every individual after clearing, in population order
+    pub fn cleared_fitness<I>(&self, population: &[I]) -> Vec<f32>
+    where
+        I: Individual,
+    {
+        let mut fitness = population
+            .iter()
+            .map(Individual::fitness)
+            .collect::<Vec<f32>>();
+        let values = values(population);
+
+        // every winner only competes with the less fit individuals after it
+        let order = by_decreasing_fitness(population);
+        for (position, &winner) in order.iter().enumerate() {
+            if fitness[winner] <= 0.0 {
+                continue;
+            }
+            let mut winners = 1;
+            for &other in order[position + 1..].iter() {
+                if fitness[other] <= 0.0 {
+                    continue;
+                }
+                let distance = (self.distance)(&values[winner], &values[other]);
+                if distance < self.radius {
+                    if winners < self.capacity {
+                        winners += 1;
+                    } else {
+                        fitness[other] = 0.0;
+                    }
+                }
+            }
+        }
+        fitness
+    }
+}
+
+impl<S> SelectionMethod for Clearing<S>
+where
+    S: SelectionMethod,
+{
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        let fitness = self.cleared_fitness(population);
+        select_with_fitness(&self.selection_method, rng, population, fitness, count)
+    }
+
+    fn selects_in_batches(&self) -> bool {
+        true
+    }
+}
+
+// Splits the population into species, gives every species a share of the
+// offspring proportional to its mean fitness and selects the parents of those
+// offspring from within the species. Parents are returned species by species
+// in pairs, so `GeneticAlgorithm` only mates individuals of the same species.
+pub struct Speciation<S> {
+    selection_method: S,
+    // Individuals closer than this to the representative of a species belong
+    // to that species
+    threshold: f32,
+    distance: Distance,
+}
+
+impl<S> Speciation<S>
+where
+    S: SelectionMethod,
+{
+    pub fn new(selection_method: S, threshold: f32) -> Self {
+        assert!(threshold > 0.0);
+        Self {
+            selection_method,
+            threshold,
+            distance: euclidean_distance,
+        }
+    }
+
+    pub fn with_distance(mut self, distance: Distance) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    // Indices of the members of every species, the fittest individual of a
+    // species is its representative and comes first
+    pub fn species<I>(&self, population: &[I]) -> Vec<Vec<usize>>
+    where
+        I: Individual,
+    {
+        let values = values(population);
+        let mut species: Vec<Vec<usize>> = Vec::new();
+        for index in by_decreasing_fitness(population) {
+            let existing = species.iter_mut().find(|members| {
+                (self.distance)(&values[members[0]], &values[index]) < self.threshold
+            });
+            match existing {
+                Some(members) => members.push(index),
+                None => species.push(vec![index]),
+            }
+        }
+        species
+    }
+
+    // Number of parents selected from every species: pairs are shared out in
+    // proportion to the mean fitness of the species by the largest remainder
+    // method, so that every species gets an even number of parents
+    fn quotas<I>(population: &[I], species: &[Vec<usize>], count: usize) -> Vec<usize>
+    where
+        I: Individual,
+    {
+        let mean_fitness = species
+            .iter()
+            .map(|members| {
+                members
+                    .iter()
+                    .map(|&index| population[index].fitness())
+                    .sum::<f32>()
+                    / members.len() as f32
+            })
+            .collect::<Vec<f32>>();
+        let total = mean_fitness.iter().sum::<f32>();
+
+        let pairs = count / 2;
+        let shares = mean_fitness
+            .iter()
+            .map(|&fitness| {
+                if total > 0.0 {
+                    pairs as f32 * fitness / total
+                } else {
+                    pairs as f32 / species.len() as f32
+                }
+            })
+            .collect::<Vec<f32>>();
+
+        let mut quotas = shares
+            .iter()
+            .map(|share| share.floor() as usize)
+            .collect::<Vec<usize>>();
+        let mut by_remainder = (0..species.len()).collect::<Vec<usize>>();
+        by_remainder.sort_by(|&a, &b| {
+            let remainder = |index: usize| shares[index] - shares[index].floor();
+            remainder(b)
+                .partial_cmp(&remainder(a))
+                .unwrap_or(Ordering::Equal)
+        });
+        let missing = pairs - quotas.iter().sum::<usize>();
+        for &index in by_remainder.iter().cycle().take(missing) {
+            quotas[index] += 1;
+        }
+
+        let mut quotas = quotas
+            .into_iter()
+            .map(|pairs| 2 * pairs)
+            .collect::<Vec<usize>>();
+        if count % 2 == 1 {
+            quotas[by_remainder[0]] += 1;
+        }
+        quotas
+    }
+}
+
+impl<S> SelectionMethod for Speciation<S>
+where
+    S: SelectionMethod,
+{
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty(), "got an empty population");
+        let species = self.species(population);
+        let quotas = Self::quotas(population, &species, count);
+
+        let mut selected = Vec::with_capacity(count);
+        for (members, quota) in species.iter().zip(quotas) {
+            if quota == 0 {
+                continue;
+            }
+            let members = members
+                .iter()
+                .map(|&index| Niched {
+                    individual: &population[index],
+                    fitness: population[index].fitness(),
+                })
+                .collect::<Vec<_>>();
+            selected.extend(
+                self.selection_method
+                    .select_many(rng, &members, quota)
+                    .into_iter()
+                    .map(|niched| niched.individual),
+            );
+        }
+        selected
+    }
+
+    fn selects_in_batches(&self) -> bool {
+        true
+    }
+}
+
+// An individual as seen by the wrapped selection method
+struct Niched<'a, I> {
+    individual: &'a I,
+    fitness: f32,
+}
+
+impl<'a, I> Individual for Niched<'a, I>
+where
+    I: Individual,
+{
+    type Gene = I::Gene;
+
+    fn fitness(&self) -> f32 {
+        self.fitness
+    }
+
+    fn chromosome(&self) -> &Chromosome<I::Gene> {
+        self.individual.chromosome()
+    }
+
+    fn create(_chromosome: Chromosome<I::Gene>) -> Self {
+        unreachable!("selection methods never create individuals")
+    }
+}
+
+pub(crate) fn select_with_fitness<'a, S, I>(
+    selection_method: &S,
+    rng: &mut dyn RngCore,
+    population: &'a [I],
+    fitness: Vec<f32>,
+    count: usize,
+) -> Vec<&'a I>
+where
+    S: SelectionMethod,
+    I: Individual,
+{
+    let niched = population
+        .iter()
+        .zip(fitness)
+        .map(|(individual, fitness)| Niched {
+            individual,
+            fitness,
+        })
+        .collect::<Vec<_>>();
+    selection_method
+        .select_many(rng, &niched, count)
+        .into_iter()
+        .map(|niched| niched.individual)
+        .collect()
+}
+
+fn values<I>(population: &[I]) -> Vec<Vec<f32>>
+where
+    I: Individual,
+{
+    population
+        .iter()
+        .map(|individual| individual.chromosome().values())
+        .collect()
+}
+
+fn by_decreasing_fitness<I>(population: &[I]) -> Vec<usize>
+where
+    I: Individual,
+{
+    let mut order = (0..population.len()).collect::<Vec<usize>>();
+    order.sort_by(|&a, &b| {
+        population[b]
+            .fitness()
+            .partial_cmp(&population[a].fitness())
+            .unwrap_or(Ordering::Equal)
+    });
+    order
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{histogram, individual, RouletteWheelSelection, TestIndividual};
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng as Cc8;
+    use std::collections::BTreeMap;
+    use std::iter::FromIterator;
+
+    // a crowded peak around fitness 10 and a lonely one at fitness 4
+    fn population() -> Vec<TestIndividual> {
+        vec![
+            individual(&[5.0, 5.0]),
+            individual(&[5.0, 4.5]),
+            individual(&[4.5, 5.0]),
+            individual(&[4.5, 4.5]),
+            individual(&[2.0, 2.0]),
+        ]
+    }
+
+    #[test]
+    fn distance() {
+        let a = Chromosome::<f32>::from_iter(vec![1.0, 2.0]);
+        let b = Chromosome::<f32>::from_iter(vec![4.0, 6.0]);
+
+        assert_relative_eq!(a.distance(&b), 5.0);
+        assert_relative_eq!(a.distance(&a), 0.0);
+    }
+
+    #[test]
+    fn fitness_sharing() {
+        let method = FitnessSharing::new(RouletteWheelSelection::new(), 1.0, 1.0);
+        let population = population();
+
+        let actual = method.shared_fitness(&population);
+        let expected = vec![4.3613024, 4.143237, 4.143237, 3.9251719, 4.0];
+        assert_relative_eq!(actual.as_slice(), expected.as_slice());
+
+        let actual_histogram = histogram(&method, &population);
+        // the lonely individual is picked about as often as any crowded one
+        let expected_histogram = BTreeMap::from_iter(vec![(4, 177), (9, 611), (10, 212)]);
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+
+    #[test]
+    fn clearing() {
+        let method = Clearing::new(RouletteWheelSelection::new(), 1.0, 1);
+        let population = population();
+
+        let actual = method.cleared_fitness(&population);
+        let expected = vec![10.0, 0.0, 0.0, 0.0, 4.0];
+        assert_relative_eq!(actual.as_slice(), expected.as_slice());
+
+        let actual_histogram = histogram(&method, &population);
+        let expected_histogram = BTreeMap::from_iter(vec![(4, 267), (10, 733)]);
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+
+    #[test]
+    fn clearing_with_larger_capacity() {
+        let method = Clearing::new(RouletteWheelSelection::new(), 1.0, 2);
+
+        let actual = method.cleared_fitness(&population());
+        let expected = vec![10.0, 9.5, 0.0, 0.0, 4.0];
+        assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn clearing_a_chain() {
+        let method = Clearing::new(RouletteWheelSelection::new(), 1.0, 2);
+        // neighbours are 0.9 apart, the ends of the chain are 1.27 apart
+        let population = vec![
+            individual(&[1.0, 1.0]),
+            individual(&[0.1, 1.0]),
+            individual(&[0.1, 0.1]),
+        ];
+
+        // the middle individual already shares the niche of the fittest one,
+        // but that doesn't count against the niche it wins itself
+        let actual = method.cleared_fitness(&population);
+        let expected = vec![2.0, 1.1, 0.2];
+        assert_relative_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    fn speciation() {
+        let method = Speciation::new(RouletteWheelSelection::new(), 1.0);
+        let population = population();
+
+        assert_eq!(method.species(&population), vec![vec![0, 1, 2, 3], vec![4]]);
+
+        let mut rng = Cc8::from_seed(Default::default());
+        let parents = method.select_many(&mut rng, &population, 10);
+        let fitness = parents
+            .iter()
+            .map(|parent| parent.fitness())
+            .collect::<Vec<f32>>();
+
+        // mean fitness 9.5 and 4.0: 5 pairs are shared out as 3.52 and 1.48
+        assert_eq!(fitness.len(), 10);
+        assert!(fitness[..8].iter().all(|&fitness| fitness >= 9.0));
+        assert!(fitness[8..].iter().all(|&fitness| fitness == 4.0));
+    }
+}
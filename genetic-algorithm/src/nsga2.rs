@@ -0,0 +1,463 @@
+// Multi-objective optimization with NSGA-II (Deb et al.), based on
+// `Individual::objectives`: individuals are ranked by Pareto dominance first
+// and by crowding distance within the same front
+
+use super::{
+    create_all, Bounds, Chromosome, CrossoverMethod, Decoder, Evaluated, Evaluator, Gene,
+    GeneticAlgorithm, Individual, MutationContext, MutationMethod, SelectionMethod, Statistics,
+};
+use rand::seq::SliceRandom;
+use rand::RngCore;
+use std::cmp::Ordering;
+
+// Whether `a` is at least as good as `b` in every objective and better in one
+pub fn dominates(a: &[f32], b: &[f32]) -> bool {
+    assert_eq!(a.len(), b.len(), "got different numbers of objectives");
+    a.iter().zip(b).all(|(a, b)| a >= b) && a.iter().zip(b).any(|(a, b)| a > b)
+}
+
+// Indices of the individuals on every front, the first front is the Pareto
+// front and individuals of later fronts are dominated by earlier ones
+pub fn non_dominated_sort<I>(population: &[I]) -> Vec<Vec<usize>>
+where
+    I: Individual,
+{
+    let objectives = population
+        .iter()
+        .map(Individual::objectives)
+        .collect::<Vec<_>>();
+    fronts(&objectives)
+}
+
+// Crowding distance of every individual of a front, in front order
+// Boundary individuals of any objective get an infinite distance
+pub fn crowding_distance<I>(population: &[I], front: &[usize]) -> Vec<f32>
+where
+    I: Individual,
+{
+    let objectives = front
+        .iter()
+        .map(|&index| population[index].objectives())
+        .collect::<Vec<_>>();
+    crowding(&objectives)
+}
+
+// The non-dominated individuals of the population
+pub fn pareto_front<I>(population: &[I]) -> Vec<&I>
+where
+    I: Individual,
+{
+    match non_dominated_sort(population).into_iter().next() {
+        Some(front) => front.into_iter().map(|index| &population[index]).collect(),
+        None => Vec::new(),
+    }
+}
+
+fn fronts(objectives: &[Vec<f32>]) -> Vec<Vec<usize>> {
+    let n = objectives.len();
+    let mut dominated = vec![Vec::new(); n];
+    let mut domination_count = vec![0; n];
+    for a in 0..n {
+        for b in 0..n {
+            if dominates(&objectives[a], &objectives[b]) {
+                dominated[a].push(b);
+            } else if dominates(&objectives[b], &objectives[a]) {
+                domination_count[a] += 1;
+            }
+        }
+    }
+
+    let mut fronts = Vec::new();
+    let mut front = (0..n)
+        .filter(|&index| domination_count[index] == 0)
+        .collect::<Vec<usize>>();
+    while !front.is_empty() {
+        let mut next = Vec::new();
+        for &a in front.iter() {
+            for &b in dominated[a].iter() {
+                domination_count[b] -= 1;
+                if domination_count[b] == 0 {
+                    next.push(b);
+                }
+            }
+        }
+        next.sort_unstable();
+        fronts.push(front);
+        front = next;
+    }
+    fronts
+}
+
+fn crowding(objectives: &[Vec<f32>]) -> Vec<f32> {
+    let n = objectives.len();
+    let mut distance = vec![0.0; n];
+    if n == 0 {
+        return distance;
+    }
+
+    for objective in 0..objectives[0].len() {
+        let values = objectives
+            .iter()
+            .map(|objectives| objectives[objective])
+            .collect::<Vec<f32>>();
+        let mut order = (0..n).collect::<Vec<usize>>();
+        order.sort_by(|&a, &b| values[a].partial_cmp(&values[b]).unwrap_or(Ordering::Equal));
+
+        let min = values[order[0]];
+        let max = values[order[n - 1]];
+        distance[order[0]] = f32::INFINITY;
+        distance[order[n - 1]] = f32::INFINITY;
+        if max - min <= 0.0 {
+            continue;
+        }
+        for window in order.windows(3) {
+            distance[window[1]] += (values[window[2]] - values[window[0]]) / (max - min);
+        }
+    }
+    distance
+}
+
+// Front index and crowding distance of every individual, in population order
+fn rank<I>(population: &[I]) -> Vec<(usize, f32)>
+where
+    I: Individual,
+{
+    let objectives = population
+        .iter()
+        .map(Individual::objectives)
+        .collect::<Vec<_>>();
+
+    let mut ranks = vec![(0, 0.0); population.len()];
+    for (rank, front) in fronts(&objectives).into_iter().enumerate() {
+        let front_objectives = front
+            .iter()
+            .map(|&index| objectives[index].clone())
+            .collect::<Vec<_>>();
+        for (&index, distance) in front.iter().zip(crowding(&front_objectives)) {
+            ranks[index] = (rank, distance);
+        }
+    }
+    ranks
+}
+
+// Lower front first, larger crowding distance within the same front
+fn crowded_comparison(a: (usize, f32), b: (usize, f32)) -> Ordering {
+    a.0.cmp(&b.0)
+        .then_with(|| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal))
+}
+
+// Binary tournament that prefers the individual on the better front and,
+// within the same front, the one in the less crowded region
+#[derive(Default)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct CrowdedTournamentSelection;
+
+impl CrowdedTournamentSelection {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl SelectionMethod for CrowdedTournamentSelection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty(), "got an empty population");
+        let ranks = rank(population);
+        let indices = (0..population.len()).collect::<Vec<usize>>();
+        (0..count)
+            .map(|_| {
+                let a = *indices.choose(rng).unwrap();
+                let b = *indices.choose(rng).unwrap();
+                match crowded_comparison(ranks[a], ranks[b]) {
+                    Ordering::Greater => &population[b],
+                    _ => &population[a],
+                }
+            })
+            .collect()
+    }
+
+    fn selects_in_batches(&self) -> bool {
+        true
+    }
+}
+
+// NSGA-II: offspring are bred from parents picked by crowded tournaments,
+// and the next generation is the best half of parents and offspring together
+// according to the crowded comparison
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct Nsga2<C, M> {
+    algorithm: GeneticAlgorithm<C, M, CrowdedTournamentSelection>,
+}
+
+impl<C, M> Nsga2<C, M> {
+    pub fn new(crossover_method: C, mutation_method: M) -> Self {
+        Self {
+            algorithm: GeneticAlgorithm::new(
+                crossover_method,
+                mutation_method,
+                CrowdedTournamentSelection::new(),
+            ),
+        }
+    }
+
+    // Genes of every offspring are repaired into these bounds after mutation
+    pub fn with_bounds(mut self, bounds: Bounds) -> Self {
+        self.algorithm = self.algorithm.with_bounds(bounds);
+        self
+    }
+
+    pub fn generation(&self) -> usize {
+        self.algorithm.generation
+    }
+
+    // Takes the population by value because the parents compete with their
+    // offspring for a place in the next generation
+    // The mutation method is adapted every generation, but without a success
+    // rate, since there is no single fitness to compare children and parents
+    pub fn evolve<I>(&mut self, rng: &mut dyn RngCore, population: Vec<I>) -> (Vec<I>, Statistics)
+    where
+        I: Individual,
+        C: CrossoverMethod<I::Gene>,
+        M: MutationMethod<I::Gene>,
+    {
+        self.evolve_with(rng, population, create_all)
+    }
+
+    // Like `evolve`, but the offspring are decoded and evaluated as one batch
+    pub fn evolve_evaluated<P, G, D, E>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        population: Vec<Evaluated<P, G>>,
+        decoder: &D,
+        evaluator: &E,
+    ) -> (Vec<Evaluated<P, G>>, Statistics)
+    where
+        G: Gene,
+        D: Decoder<G, Phenotype = P>,
+        E: Evaluator<P>,
+        C: CrossoverMethod<G>,
+        M: MutationMethod<G>,
+    {
+        self.evolve_with(rng, population, |children| {
+            Evaluated::evaluate_all(decoder, evaluator, children)
+        })
+    }
+
+    fn evolve_with<I, F>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        population: Vec<I>,
+        create: F,
+    ) -> (Vec<I>, Statistics)
+    where
+        I: Individual,
+        C: CrossoverMethod<I::Gene>,
+        M: MutationMethod<I::Gene>,
+        F: FnOnce(Vec<Chromosome<I::Gene>>) -> Vec<I>,
+    {
+        assert!(!population.is_empty());
+        let algorithm = &mut self.algorithm;
+        algorithm.mutation_method.adapt(&MutationContext {
+            generation: algorithm.generation,
+            success_rate: None,
+        });
+
+        let size = population.len();
+        let (_, children) = algorithm.breed(rng, &population, size);
+        let offspring = create(children);
+        algorithm.generation += 1;
+
+        let statistics = Statistics::new(&population);
+
+        let mut combined = population;
+        combined.extend(offspring);
+        let ranks = rank(&combined);
+        let mut order = (0..combined.len()).collect::<Vec<usize>>();
+        order.sort_by(|&a, &b| crowded_comparison(ranks[a], ranks[b]));
+
+        let mut survivors = vec![false; combined.len()];
+        for &index in order.iter().take(size) {
+            survivors[index] = true;
+        }
+        let next_generation = combined
+            .into_iter()
+            .zip(survivors)
+            .filter(|(_, survives)| *survives)
+            .map(|(individual, _)| individual)
+            .collect();
+
+        (next_generation, statistics)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Identity, NormalMutation, Sum, TestIndividual, UniformCrossover};
+    use approx::assert_relative_eq;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng as Cc8;
+
+    // Schaffer's problem: -x^2 and -(x - 2)^2 are maximized, every x in
+    // [0, 2] is Pareto optimal
+    #[derive(Debug)]
+    struct Schaffer {
+        chromosome: Chromosome,
+    }
+
+    impl Individual for Schaffer {
+        type Gene = f32;
+
+        fn fitness(&self) -> f32 {
+            self.objectives().iter().sum()
+        }
+
+        fn chromosome(&self) -> &Chromosome {
+            &self.chromosome
+        }
+
+        fn objectives(&self) -> Vec<f32> {
+            let x = self.chromosome[0];
+            vec![-x * x, -(x - 2.0) * (x - 2.0)]
+        }
+
+        fn create(chromosome: Chromosome) -> Self {
+            Self { chromosome }
+        }
+    }
+
+    fn population(xs: &[f32]) -> Vec<Schaffer> {
+        xs.iter()
+            .map(|&x| Schaffer::create(vec![x].into_iter().collect()))
+            .collect()
+    }
+
+    #[test]
+    fn dominance() {
+        assert!(dominates(&[1.0, 2.0], &[1.0, 1.0]));
+        assert!(!dominates(&[1.0, 1.0], &[1.0, 1.0]));
+        assert!(!dominates(&[2.0, 0.0], &[1.0, 1.0]));
+    }
+
+    #[test]
+    fn non_dominated_sorting() {
+        let population = population(&[1.0, -1.0, 3.0, 0.5, -2.0, 2.0]);
+
+        let fronts = non_dominated_sort(&population);
+
+        assert_eq!(fronts, vec![vec![0, 3, 5], vec![1, 2], vec![4]]);
+        let front = pareto_front(&population)
+            .iter()
+            .map(|individual| individual.chromosome()[0])
+            .collect::<Vec<f32>>();
+        assert_eq!(front, vec![1.0, 0.5, 2.0]);
+    }
+
+    #[test]
+    fn crowding_distances() {
+        let population = population(&[0.0, 0.5, 1.0, 2.0]);
+
+        let actual = crowding_distance(&population, &[0, 1, 2, 3]);
+
+        // the objectives span 4.0 each, the gaps around 0.5 are 1.0 and 3.0,
+        // the gaps around 1.0 are 3.75 and 2.25
+        assert_eq!(actual[0], f32::INFINITY);
+        assert_relative_eq!(actual[1], 0.25 + 0.75);
+        assert_relative_eq!(actual[2], 0.9375 + 0.5625);
+        assert_eq!(actual[3], f32::INFINITY);
+    }
+
+    #[test]
+    fn nsga2_converges_to_the_pareto_front() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let mut nsga2 = Nsga2::new(UniformCrossover::new(), NormalMutation::new(1.0, 0.2));
+
+        let mut population = population(&[-4.0, -3.0, -2.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+        for _ in 0..30 {
+            population = nsga2.evolve(&mut rng, population).0;
+        }
+
+        assert_eq!(nsga2.generation(), 30);
+        assert_eq!(population.len(), 8);
+        assert_eq!(pareto_front(&population).len(), 8);
+        let xs = population
+            .iter()
+            .map(|individual| individual.chromosome()[0])
+            .collect::<Vec<f32>>();
+        assert!(xs.iter().all(|&x| (-0.1..=2.1).contains(&x)));
+        // the crowding distance keeps the front spread out
+        let min = xs.iter().cloned().fold(f32::MAX, f32::min);
+        let max = xs.iter().cloned().fold(f32::MIN, f32::max);
+        assert!(max - min > 1.5);
+    }
+
+    #[test]
+    fn nsga2_with_bounds() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let bounds = Bounds::uniform(1, 0.5, 1.5);
+        let mut nsga2 = Nsga2::new(UniformCrossover::new(), NormalMutation::new(1.0, 2.0))
+            .with_bounds(bounds.clone());
+
+        let mut population = population(&[0.5, 0.75, 1.0, 1.25, 1.5]);
+        for _ in 0..10 {
+            population = nsga2.evolve(&mut rng, population).0;
+            assert!(population
+                .iter()
+                .all(|individual| bounds.contains(individual.chromosome())));
+        }
+    }
+
+    #[test]
+    fn evaluated_nsga2_matches_individuals() {
+        let chromosomes = (0..6)
+            .map(|n| vec![n as f32, 1.0].into_iter().collect())
+            .collect::<Vec<Chromosome>>();
+        let nsga2 = || Nsga2::new(UniformCrossover::new(), NormalMutation::new(0.5, 0.5));
+
+        let mut rng = Cc8::from_seed(Default::default());
+        let mut evaluated_nsga2 = nsga2();
+        let evaluator = Sum::default();
+        let mut evaluated = Evaluated::evaluate_all(&Identity, &evaluator, chromosomes.clone());
+        for _ in 0..5 {
+            evaluated = evaluated_nsga2
+                .evolve_evaluated(&mut rng, evaluated, &Identity, &evaluator)
+                .0;
+        }
+
+        let mut rng = Cc8::from_seed(Default::default());
+        let mut nsga2 = nsga2();
+        let mut individuals = chromosomes
+            .into_iter()
+            .map(TestIndividual::create)
+            .collect::<Vec<_>>();
+        for _ in 0..5 {
+            individuals = nsga2.evolve(&mut rng, individuals).0;
+        }
+
+        // the surviving parents are never evaluated again
+        assert_eq!(*evaluator.batches.lock().unwrap(), vec![6; 6]);
+        let evaluated = evaluated
+            .iter()
+            .map(|individual| individual.chromosome().clone())
+            .collect::<Vec<Chromosome>>();
+        let individuals = individuals
+            .iter()
+            .map(|individual| individual.chromosome().clone())
+            .collect::<Vec<Chromosome>>();
+        assert_eq!(evaluated, individuals);
+    }
+}
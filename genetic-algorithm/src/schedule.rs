@@ -0,0 +1,77 @@
+// A parameter (e.g. a mutation step size) that changes with the generation
+pub trait Schedule {
+    fn value(&self, generation: usize) -> f32;
+}
+
+// Moves linearly from `start` to `end` over `generations` generations and
+// stays at `end` afterwards
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinearDecay {
+    start: f32,
+    end: f32,
+    generations: usize,
+}
+
+impl LinearDecay {
+    pub fn new(start: f32, end: f32, generations: usize) -> Self {
+        assert!(generations > 0);
+        Self {
+            start,
+            end,
+            generations,
+        }
+    }
+}
+
+impl Schedule for LinearDecay {
+    fn value(&self, generation: usize) -> f32 {
+        let progress = generation.min(self.generations) as f32 / self.generations as f32;
+        self.start + (self.end - self.start) * progress
+    }
+}
+
+// Multiplies `start` by `rate` every generation, but never goes below `min`
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExponentialDecay {
+    start: f32,
+    rate: f32,
+    min: f32,
+}
+
+impl ExponentialDecay {
+    pub fn new(start: f32, rate: f32, min: f32) -> Self {
+        assert!(rate > 0.0 && rate <= 1.0);
+        Self { start, rate, min }
+    }
+}
+
+impl Schedule for ExponentialDecay {
+    fn value(&self, generation: usize) -> f32 {
+        (self.start * self.rate.powi(generation as i32)).max(self.min)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn linear_decay() {
+        let schedule = LinearDecay::new(1.0, 0.2, 4);
+
+        assert_relative_eq!(schedule.value(0), 1.0);
+        assert_relative_eq!(schedule.value(1), 0.8);
+        assert_relative_eq!(schedule.value(4), 0.2);
+        assert_relative_eq!(schedule.value(100), 0.2);
+    }
+
+    #[test]
+    fn exponential_decay() {
+        let schedule = ExponentialDecay::new(1.0, 0.5, 0.1);
+
+        assert_relative_eq!(schedule.value(0), 1.0);
+        assert_relative_eq!(schedule.value(2), 0.25);
+        assert_relative_eq!(schedule.value(10), 0.1);
+    }
+}
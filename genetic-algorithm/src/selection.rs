@@ -1,13 +1,39 @@
 use super::Individual;
+use rand::distributions::{Distribution, WeightedIndex};
 use rand::seq::SliceRandom;
-use rand::RngCore;
+use rand::{Rng, RngCore};
+use std::cmp::Ordering;
 
 pub trait SelectionMethod {
     fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
     where
         I: Individual;
+
+    // Selects the whole mating pool at once, methods that need to see every
+    // pick together (e.g. stochastic universal sampling) override this along
+    // with `selects_in_batches`
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        (0..count).map(|_| self.select(rng, population)).collect()
+    }
+
+    // Whether `GeneticAlgorithm` selects the whole mating pool with
+    // `select_many` before breeding, otherwise the two parents of every child
+    // are selected right before it's bred
+    fn selects_in_batches(&self) -> bool {
+        false
+    }
 }
 
+#[derive(Default)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
 pub struct RouletteWheelSelection;
 
 impl RouletteWheelSelection {
@@ -27,10 +53,225 @@ impl SelectionMethod for RouletteWheelSelection {
     }
 }
 
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct TournamentSelection {
+    // Number of individuals drawn (with replacement) into a tournament
+    size: usize,
+    // Probability of the fittest contestant winning the tournament:
+    // the second fittest wins with pressure * (1 - pressure) and so on
+    // 1.0 = the fittest contestant always wins
+    pressure: f32,
+}
+
+impl TournamentSelection {
+    pub fn new(size: usize, pressure: f32) -> Self {
+        assert!(size > 0);
+        assert!(pressure > 0.0 && pressure <= 1.0);
+        Self { size, pressure }
+    }
+}
+
+impl SelectionMethod for TournamentSelection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty(), "got an empty population");
+        let mut contestants = (0..self.size)
+            .map(|_| population.choose(rng).unwrap())
+            .collect::<Vec<&I>>();
+        contestants.sort_by(|a, b| {
+            b.fitness()
+                .partial_cmp(&a.fitness())
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let last = contestants.len() - 1;
+        for contestant in contestants.iter().take(last) {
+            if rng.gen_bool(self.pressure as _) {
+                return contestant;
+            }
+        }
+        contestants[last]
+    }
+}
+
+// Roulette wheel with `count` equally spaced pointers that is spun only once
+#[derive(Default)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct StochasticUniversalSampling;
+
+impl StochasticUniversalSampling {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl SelectionMethod for StochasticUniversalSampling {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    // The picks are shuffled so that consecutive parents are not neighbours
+    // in the population
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty(), "got an empty population");
+        assert!(
+            population
+                .iter()
+                .all(|individual| individual.fitness() >= 0.0),
+            "got a negative fitness"
+        );
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let total = population.iter().map(Individual::fitness).sum::<f32>();
+        if total <= 0.0 {
+            return (0..count)
+                .map(|_| population.choose(rng).unwrap())
+                .collect();
+        }
+
+        let spacing = total / count as f32;
+        let start = rng.gen_range(0.0..spacing);
+
+        let mut selected = Vec::with_capacity(count);
+        let mut individuals = population.iter();
+        let mut current = individuals.next().unwrap();
+        let mut cumulative = current.fitness();
+        for pointer in (0..count).map(|i| start + i as f32 * spacing) {
+            while cumulative <= pointer {
+                match individuals.next() {
+                    Some(next) => {
+                        current = next;
+                        cumulative += current.fitness();
+                    }
+                    // rounding errors can leave the last pointers past the end
+                    None => break,
+                }
+            }
+            selected.push(current);
+        }
+
+        selected.shuffle(rng);
+        selected
+    }
+
+    fn selects_in_batches(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub enum Ranking {
+    // The fittest individual is `pressure` times as likely to be selected as
+    // an average one, the selection probability decreases linearly with rank
+    // 1.0 = uniform selection
+    // 2.0 = the least fit individual is never selected
+    Linear { pressure: f32 },
+    // Selection probability is proportional to base^rank, where the fittest
+    // individual has rank 0
+    Exponential { base: f32 },
+}
+
+// Selection probabilities depend only on the fitness order of individuals,
+// so the scale and the sign of fitness values don't matter
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct RankSelection {
+    ranking: Ranking,
+}
+
+impl RankSelection {
+    pub fn linear(pressure: f32) -> Self {
+        assert!((1.0..=2.0).contains(&pressure));
+        Self {
+            ranking: Ranking::Linear { pressure },
+        }
+    }
+
+    pub fn exponential(base: f32) -> Self {
+        assert!(base > 0.0 && base < 1.0);
+        Self {
+            ranking: Ranking::Exponential { base },
+        }
+    }
+
+    // Selection weight of every individual, in population order
+    fn weights<I>(&self, population: &[I]) -> Vec<f32>
+    where
+        I: Individual,
+    {
+        let n = population.len();
+        let mut order = (0..n).collect::<Vec<usize>>();
+        order.sort_by(|&a, &b| {
+            population[b]
+                .fitness()
+                .partial_cmp(&population[a].fitness())
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let mut weights = vec![0.0; n];
+        for (rank, &index) in order.iter().enumerate() {
+            weights[index] = match self.ranking {
+                Ranking::Linear { pressure } if n > 1 => {
+                    pressure - 2.0 * (pressure - 1.0) * rank as f32 / (n - 1) as f32
+                }
+                Ranking::Linear { .. } => 1.0,
+                Ranking::Exponential { base } => base.powi(rank as i32),
+            };
+        }
+        weights
+    }
+}
+
+impl SelectionMethod for RankSelection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty(), "got an empty population");
+        let weights = self.weights(population);
+        // weights of the fittest individuals are always positive
+        let distribution = WeightedIndex::new(&weights).unwrap();
+        (0..count)
+            .map(|_| &population[distribution.sample(rng)])
+            .collect()
+    }
+
+    fn selects_in_batches(&self) -> bool {
+        true
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::TestIndividual;
+    use crate::{histogram, population, TestIndividual};
 
     use rand::SeedableRng;
     use rand_chacha::ChaCha8Rng as Cc8;
@@ -60,4 +301,59 @@ mod test {
 
         assert_eq!(actual_histogram, expected_histogram);
     }
+
+    #[test]
+    fn tournament_selection() {
+        let population = population(&[2.0, 1.0, 4.0, 3.0]);
+
+        let actual_histogram = histogram(&TournamentSelection::new(2, 0.8), &population);
+
+        let expected_histogram = BTreeMap::from_iter(vec![(1, 153), (2, 199), (3, 268), (4, 380)]);
+
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+
+    #[test]
+    fn tournament_selection_with_negative_fitness() {
+        let population = population(&[-2.0, -1.0, -4.0, -3.0]);
+
+        let actual_histogram = histogram(&TournamentSelection::new(3, 1.0), &population);
+
+        let expected_histogram =
+            BTreeMap::from_iter(vec![(-4, 23), (-3, 107), (-2, 300), (-1, 570)]);
+
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+
+    #[test]
+    fn linear_rank_selection() {
+        let population = population(&[2.0, 1.0, 40.0, 3.0]);
+
+        let actual_histogram = histogram(&RankSelection::linear(1.5), &population);
+
+        let expected_histogram = BTreeMap::from_iter(vec![(1, 116), (2, 211), (3, 274), (40, 399)]);
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+
+    #[test]
+    fn exponential_rank_selection() {
+        let population = population(&[-2.0, -1.0, -40.0, -3.0]);
+
+        let actual_histogram = histogram(&RankSelection::exponential(0.5), &population);
+
+        let expected_histogram =
+            BTreeMap::from_iter(vec![(-40, 60), (-3, 123), (-2, 264), (-1, 553)]);
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+
+    #[test]
+    fn stochastic_universal_sampling() {
+        let population = population(&[2.0, 1.0, 4.0, 3.0]);
+
+        let actual_histogram = histogram(&StochasticUniversalSampling::new(), &population);
+
+        // every individual gets exactly its expected number of copies
+        let expected_histogram = BTreeMap::from_iter(vec![(1, 100), (2, 200), (3, 300), (4, 400)]);
+        assert_eq!(actual_histogram, expected_histogram);
+    }
 }
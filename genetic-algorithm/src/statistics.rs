@@ -0,0 +1,134 @@
+use super::{Gene, Individual};
+use std::cmp::Ordering;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Statistics {
+    min_fitness: f32,
+    max_fitness: f32,
+    mean_fitness: f32,
+    median_fitness: f32,
+    std_dev_fitness: f32,
+    diversity: f32,
+}
+
+impl Statistics {
+    pub fn new<I>(population: &[I]) -> Self
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty());
+
+        let mut fitness = population
+            .iter()
+            .map(Individual::fitness)
+            .collect::<Vec<f32>>();
+        fitness.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        let n = fitness.len();
+        let median_fitness = if n % 2 == 0 {
+            0.5 * (fitness[n / 2 - 1] + fitness[n / 2])
+        } else {
+            fitness[n / 2]
+        };
+
+        let (mean_fitness, std_dev_fitness) = mean_and_std_dev(fitness.iter().copied());
+
+        // per-gene standard deviation across the population, averaged over the genes
+        let genes = population[0].chromosome().len();
+        let diversity = if genes == 0 {
+            0.0
+        } else {
+            (0..genes)
+                .map(|gene| {
+                    let values = population
+                        .iter()
+                        .map(|individual| individual.chromosome()[gene].value());
+                    mean_and_std_dev(values).1
+                })
+                .sum::<f32>()
+                / genes as f32
+        };
+
+        Self {
+            min_fitness: fitness[0],
+            max_fitness: fitness[n - 1],
+            mean_fitness,
+            median_fitness,
+            std_dev_fitness,
+            diversity,
+        }
+    }
+
+    pub fn min_fitness(&self) -> f32 {
+        self.min_fitness
+    }
+
+    pub fn max_fitness(&self) -> f32 {
+        self.max_fitness
+    }
+
+    pub fn mean_fitness(&self) -> f32 {
+        self.mean_fitness
+    }
+
+    pub fn median_fitness(&self) -> f32 {
+        self.median_fitness
+    }
+
+    // Population (not sample) standard deviation
+    pub fn std_dev_fitness(&self) -> f32 {
+        self.std_dev_fitness
+    }
+
+    // Average standard deviation of the genes across the population,
+    // 0.0 = every individual has the same chromosome
+    pub fn diversity(&self) -> f32 {
+        self.diversity
+    }
+}
+
+fn mean_and_std_dev(values: impl Iterator<Item = f32> + Clone) -> (f32, f32) {
+    let n = values.clone().count() as f32;
+    let mean = values.clone().sum::<f32>() / n;
+    let variance = values.map(|x| (x - mean).powi(2)).sum::<f32>() / n;
+    (mean, variance.sqrt())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::individual;
+
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn statistics() {
+        let population = vec![
+            individual(&[0.0, 0.0, 0.0]), // fitness = 0.0
+            individual(&[1.0, 1.0, 1.0]), // fitness = 3.0
+            individual(&[1.0, 2.0, 1.0]), // fitness = 4.0
+            individual(&[1.0, 2.0, 6.0]), // fitness = 9.0
+        ];
+
+        let statistics = Statistics::new(&population);
+
+        assert_relative_eq!(statistics.min_fitness(), 0.0);
+        assert_relative_eq!(statistics.max_fitness(), 9.0);
+        assert_relative_eq!(statistics.mean_fitness(), 4.0);
+        assert_relative_eq!(statistics.median_fitness(), 3.5);
+        assert_relative_eq!(statistics.std_dev_fitness(), 3.2403703);
+        // gene-wise: 0.4330127, 0.8291562, 2.3452079
+        assert_relative_eq!(statistics.diversity(), 1.2024589);
+    }
+
+    #[test]
+    fn converged_population_has_no_diversity() {
+        let population = vec![individual(&[1.0, 2.0]), individual(&[1.0, 2.0])];
+
+        let statistics = Statistics::new(&population);
+
+        assert_relative_eq!(statistics.diversity(), 0.0);
+        assert_relative_eq!(statistics.std_dev_fitness(), 0.0);
+        assert_relative_eq!(statistics.median_fitness(), 3.0);
+    }
+}
@@ -0,0 +1,411 @@
+// Steady-state evolution: instead of replacing the whole generation, every
+// step breeds a few children, and each of them takes the place of an
+// individual chosen by a `ReplacementMethod`
+
+use super::{
+    create_all, Chromosome, CrossoverMethod, Decoder, Evaluated, Evaluator, Gene, GeneticAlgorithm,
+    Individual, MutationContext, MutationMethod, SelectionMethod, Statistics,
+};
+use rand::seq::index;
+use rand::{Rng, RngCore};
+use std::cmp::Ordering;
+
+pub trait ReplacementMethod {
+    // Index of the individual that makes room for a child, `ages` holds the
+    // number of steps every individual has survived
+    fn replace<I>(&self, rng: &mut dyn RngCore, population: &[I], ages: &[usize]) -> usize
+    where
+        I: Individual;
+}
+
+#[derive(Default)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct WorstReplacement;
+
+impl WorstReplacement {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ReplacementMethod for WorstReplacement {
+    fn replace<I>(&self, _rng: &mut dyn RngCore, population: &[I], _ages: &[usize]) -> usize
+    where
+        I: Individual,
+    {
+        least_fit(population, 0..population.len())
+    }
+}
+
+// The least fit of the oldest individuals is replaced
+#[derive(Default)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct OldestReplacement;
+
+impl OldestReplacement {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ReplacementMethod for OldestReplacement {
+    fn replace<I>(&self, _rng: &mut dyn RngCore, population: &[I], ages: &[usize]) -> usize
+    where
+        I: Individual,
+    {
+        let oldest = ages.iter().copied().max().expect("got an empty population");
+        least_fit(
+            population,
+            (0..ages.len()).filter(|&index| ages[index] == oldest),
+        )
+    }
+}
+
+#[derive(Default)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct RandomReplacement;
+
+impl RandomReplacement {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ReplacementMethod for RandomReplacement {
+    fn replace<I>(&self, rng: &mut dyn RngCore, population: &[I], _ages: &[usize]) -> usize
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty(), "got an empty population");
+        rng.gen_range(0..population.len())
+    }
+}
+
+// The least fit of `size` randomly drawn (distinct) individuals is replaced
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct InverseTournamentReplacement {
+    size: usize,
+}
+
+impl InverseTournamentReplacement {
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0);
+        Self { size }
+    }
+}
+
+impl ReplacementMethod for InverseTournamentReplacement {
+    fn replace<I>(&self, rng: &mut dyn RngCore, population: &[I], _ages: &[usize]) -> usize
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty(), "got an empty population");
+        let size = self.size.min(population.len());
+        least_fit(
+            population,
+            index::sample(rng, population.len(), size).into_iter(),
+        )
+    }
+}
+
+// Index of the least fit individual among `indices`, the first one on ties
+fn least_fit<I>(population: &[I], indices: impl Iterator<Item = usize>) -> usize
+where
+    I: Individual,
+{
+    indices
+        .min_by(|&a, &b| {
+            population[a]
+                .fitness()
+                .partial_cmp(&population[b].fitness())
+                .unwrap_or(Ordering::Equal)
+        })
+        .expect("got an empty population")
+}
+
+// Runs a `GeneticAlgorithm` in steady-state mode, the elitism of the algorithm
+// is not used, since every individual not picked for replacement survives
+pub struct SteadyState<C, M, S, R, I> {
+    algorithm: GeneticAlgorithm<C, M, S>,
+    replacement_method: R,
+    population: Vec<I>,
+    // Number of steps every individual has survived, in population order
+    ages: Vec<usize>,
+    // Number of children bred in every step
+    offspring_count: usize,
+}
+
+impl<C, M, S, R, I> SteadyState<C, M, S, R, I>
+where
+    C: CrossoverMethod<I::Gene>,
+    M: MutationMethod<I::Gene>,
+    S: SelectionMethod,
+    R: ReplacementMethod,
+    I: Individual,
+{
+    pub fn new(
+        algorithm: GeneticAlgorithm<C, M, S>,
+        replacement_method: R,
+        population: Vec<I>,
+    ) -> Self {
+        assert!(!population.is_empty(), "got an empty population");
+        Self {
+            algorithm,
+            replacement_method,
+            ages: vec![0; population.len()],
+            population,
+            offspring_count: 1,
+        }
+    }
+
+    pub fn with_offspring_count(mut self, offspring_count: usize) -> Self {
+        assert!(offspring_count > 0);
+        self.offspring_count = offspring_count;
+        self
+    }
+
+    pub fn algorithm(&self) -> &GeneticAlgorithm<C, M, S> {
+        &self.algorithm
+    }
+
+    pub fn population(&self) -> &[I] {
+        &self.population
+    }
+
+    pub fn ages(&self) -> &[usize] {
+        &self.ages
+    }
+
+    // Number of steps taken so far
+    pub fn step_count(&self) -> usize {
+        self.algorithm.generation
+    }
+
+    pub fn into_population(self) -> Vec<I> {
+        self.population
+    }
+
+    // Breeds `offspring_count` children and puts them into the population,
+    // returns the statistics of the population before the step
+    pub fn step(&mut self, rng: &mut dyn RngCore) -> Statistics {
+        self.step_with(rng, create_all)
+    }
+
+    // The mutation method is adapted every step, with the fraction of the
+    // last step's children that outperformed both of their parents
+    fn step_with<F>(&mut self, rng: &mut dyn RngCore, create: F) -> Statistics
+    where
+        F: FnOnce(Vec<Chromosome<I::Gene>>) -> Vec<I>,
+    {
+        let algorithm = &mut self.algorithm;
+        algorithm.mutation_method.adapt(&MutationContext {
+            generation: algorithm.generation,
+            success_rate: algorithm.success_rate,
+        });
+
+        let (parents, children) = algorithm.breed(rng, &self.population, self.offspring_count);
+        let children = create(children);
+
+        let successes = parents
+            .chunks(2)
+            .zip(children.iter())
+            .filter(|(parents, child)| {
+                child.fitness() > parents[0].fitness().max(parents[1].fitness())
+            })
+            .count();
+        algorithm.success_rate = Some(successes as f32 / children.len() as f32);
+        algorithm.generation += 1;
+
+        let statistics = Statistics::new(&self.population);
+        self.ages.iter_mut().for_each(|age| *age += 1);
+        for child in children {
+            let index = self
+                .replacement_method
+                .replace(rng, &self.population, &self.ages);
+            self.population[index] = child;
+            self.ages[index] = 0;
+        }
+        statistics
+    }
+}
+
+impl<C, M, S, R, P, G> SteadyState<C, M, S, R, Evaluated<P, G>>
+where
+    C: CrossoverMethod<G>,
+    M: MutationMethod<G>,
+    S: SelectionMethod,
+    R: ReplacementMethod,
+    G: Gene,
+{
+    // Like `step`, but the children are decoded and evaluated as one batch
+    pub fn step_evaluated<D, E>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        decoder: &D,
+        evaluator: &E,
+    ) -> Statistics
+    where
+        D: Decoder<G, Phenotype = P>,
+        E: Evaluator<P>,
+    {
+        self.step_with(rng, |children| {
+            Evaluated::evaluate_all(decoder, evaluator, children)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        individual, population, Identity, RouletteWheelSelection, Sum, TestIndividual,
+        TournamentSelection, UniformCrossover, UniformMutation,
+    };
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng as Cc8;
+    use std::collections::BTreeMap;
+    use std::iter::FromIterator;
+
+    // How often every index is picked when replacing 1000 times
+    fn replacement_histogram<R>(method: &R, population: &[TestIndividual]) -> BTreeMap<usize, usize>
+    where
+        R: ReplacementMethod,
+    {
+        let mut rng = Cc8::from_seed(Default::default());
+        let ages = vec![0; population.len()];
+        (0..1000).fold(BTreeMap::new(), |mut histogram, _| {
+            *histogram
+                .entry(method.replace(&mut rng, population, &ages))
+                .or_insert(0) += 1;
+            histogram
+        })
+    }
+
+    #[test]
+    fn worst_replacement() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let population = population(&[3.0, 1.0, 4.0, 1.0]);
+
+        let index = WorstReplacement::new().replace(&mut rng, &population, &[0; 4]);
+        assert_eq!(index, 1);
+    }
+
+    #[test]
+    fn oldest_replacement() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let population = population(&[3.0, 1.0, 4.0, 2.0]);
+
+        let index = OldestReplacement::new().replace(&mut rng, &population, &[5, 2, 7, 7]);
+        assert_eq!(index, 3);
+    }
+
+    #[test]
+    fn random_replacement() {
+        let population = population(&[3.0, 1.0, 4.0, 2.0]);
+
+        let actual_histogram = replacement_histogram(&RandomReplacement::new(), &population);
+        let expected_histogram = BTreeMap::from_iter(vec![(0, 243), (1, 265), (2, 261), (3, 231)]);
+
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+
+    #[test]
+    fn inverse_tournament_replacement() {
+        let population = population(&[3.0, 1.0, 4.0, 2.0]);
+
+        // the fittest individual never loses a tournament of two
+        let actual_histogram =
+            replacement_histogram(&InverseTournamentReplacement::new(2), &population);
+        let expected_histogram = BTreeMap::from_iter(vec![(0, 184), (1, 502), (3, 314)]);
+
+        assert_eq!(actual_histogram, expected_histogram);
+    }
+
+    fn algorithm() -> GeneticAlgorithm<UniformCrossover, UniformMutation, RouletteWheelSelection> {
+        GeneticAlgorithm::new(
+            UniformCrossover::new(),
+            UniformMutation::new(0.5, 0.5),
+            RouletteWheelSelection::new(),
+        )
+    }
+
+    #[test]
+    fn steady_state() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let population = (0..8)
+            .map(|n| individual(&[n as f32, 1.0, (n % 3) as f32]))
+            .collect();
+        let mut steady_state = SteadyState::new(algorithm(), WorstReplacement::new(), population)
+            .with_offspring_count(2);
+
+        let max_fitness = |population: &[TestIndividual]| {
+            population
+                .iter()
+                .map(Individual::fitness)
+                .fold(f32::MIN, f32::max)
+        };
+
+        let mut best = max_fitness(steady_state.population());
+        for _ in 0..50 {
+            let statistics = steady_state.step(&mut rng);
+            assert_eq!(statistics.max_fitness(), best);
+
+            // the fittest individual is never the worst one
+            let next_best = max_fitness(steady_state.population());
+            assert!(next_best >= best);
+            best = next_best;
+
+            assert_eq!(steady_state.population().len(), 8);
+            assert!(steady_state.ages().iter().filter(|&&age| age == 0).count() <= 2);
+        }
+        assert_eq!(steady_state.step_count(), 50);
+        assert!(best > 10.0);
+    }
+
+    #[test]
+    fn ages_are_tracked() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let population = (0..4).map(|n| individual(&[n as f32, 1.0])).collect();
+        let mut steady_state = SteadyState::new(algorithm(), OldestReplacement::new(), population);
+
+        // every step replaces one of the individuals that have been there
+        // from the start
+        for step in 1..=4 {
+            steady_state.step(&mut rng);
+
+            let mut ages = steady_state.ages().to_vec();
+            ages.sort_unstable();
+            let mut expected = (0..step).collect::<Vec<usize>>();
+            expected.resize(4, step);
+            assert_eq!(ages, expected);
+        }
+    }
+
+    #[test]
+    fn steady_state_with_evaluation() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let evaluator = Sum::default();
+        let chromosomes = (0..8)
+            .map(|n| vec![n as f32, 1.0].into_iter().collect())
+            .collect();
+        let population = Evaluated::evaluate_all(&Identity, &evaluator, chromosomes);
+        let ga = GeneticAlgorithm::new(
+            UniformCrossover::new(),
+            UniformMutation::new(0.5, 0.5),
+            TournamentSelection::new(2, 0.9),
+        );
+        let mut steady_state =
+            SteadyState::new(ga, InverseTournamentReplacement::new(3), population)
+                .with_offspring_count(3);
+
+        for _ in 0..4 {
+            steady_state.step_evaluated(&mut rng, &Identity, &evaluator);
+        }
+
+        assert_eq!(*evaluator.batches.lock().unwrap(), vec![8, 3, 3, 3, 3]);
+        assert!(steady_state
+            .population()
+            .iter()
+            .all(|individual| individual.fitness() == individual.phenotype().iter().sum::<f32>()));
+    }
+}
@@ -1,6 +1,54 @@
+mod serialization;
+
+pub use serialization::FORMAT_VERSION;
+
 use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    // A network needs at least an input and an output layer
+    InvalidTopology { layers: usize },
+    InvalidWeightCount { expected: usize, actual: usize },
+    UnsupportedVersion { version: u32 },
+    Malformed(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidTopology { layers } => write!(
+                f,
+                "invalid topology: expected at least 2 layers, got {}",
+                layers
+            ),
+            Self::InvalidWeightCount { expected, actual } => write!(
+                f,
+                "invalid number of weights: the topology requires {}, got {}",
+                expected, actual
+            ),
+            Self::UnsupportedVersion { version } => write!(
+                f,
+                "unsupported format version {}, expected {}",
+                version, FORMAT_VERSION
+            ),
+            Self::Malformed(reason) => write!(f, "malformed network: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(
+    into = "serialization::NetworkFile",
+    try_from = "serialization::NetworkFile"
+)]
 pub struct Network {
+    // Kept apart from the layers, which can't tell it when the first hidden
+    // layer has no neurons
+    inputs: usize,
     layers: Vec<Layer>,
 }
 
@@ -8,11 +56,56 @@ impl Network {
     pub fn random(rng: &mut dyn rand::RngCore, layers: &[LayerTopology]) -> Self {
         assert!(layers.len() > 1);
 
+        let inputs = layers[0].neurons;
         let layers = layers
             .windows(2)
-            .map(|layers| Layer::random(rng, layers[0].neurons, layers[1].neurons))
+            .map(|layers| Layer::random(rng, layers[0].neurons, &layers[1]))
             .collect();
-        Self { layers }
+        Self { inputs, layers }
+    }
+
+    pub fn from_weights(
+        layers: &[LayerTopology],
+        weights: impl IntoIterator<Item = f32>,
+    ) -> Result<Self, Error> {
+        let expected = Self::weight_count(layers)?;
+        let weights = weights.into_iter().collect::<Vec<f32>>();
+        if weights.len() != expected {
+            return Err(Error::InvalidWeightCount {
+                expected,
+                actual: weights.len(),
+            });
+        }
+
+        let inputs = layers[0].neurons;
+        let mut weights = weights.into_iter();
+        let layers = layers
+            .windows(2)
+            .map(|layers| Layer::from_weights(layers[0].neurons, &layers[1], &mut weights))
+            .collect();
+
+        Ok(Self { inputs, layers })
+    }
+
+    // Number of parameters (biases and weights) of a network with the given topology
+    pub fn weight_count(layers: &[LayerTopology]) -> Result<usize, Error> {
+        if layers.len() < 2 {
+            return Err(Error::InvalidTopology {
+                layers: layers.len(),
+            });
+        }
+
+        // the sizes can come from a file, so they must not overflow
+        layers
+            .windows(2)
+            .try_fold(0usize, |count, layers| {
+                layers[0]
+                    .neurons
+                    .checked_add(1)
+                    .and_then(|inputs| inputs.checked_mul(layers[1].neurons))
+                    .and_then(|weights| count.checked_add(weights))
+            })
+            .ok_or_else(|| Error::Malformed("the topology has too many weights".to_string()))
     }
 
     pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
@@ -20,28 +113,69 @@ impl Network {
             .iter()
             .fold(inputs, |inputs, layer| layer.propagate(inputs))
     }
+
+    // The input layer's activation is not stored, it's reported as linear
+    pub fn topology(&self) -> Vec<LayerTopology> {
+        std::iter::once(LayerTopology {
+            neurons: self.inputs,
+            activation: Activation::Linear,
+        })
+        .chain(self.layers.iter().map(|layer| LayerTopology {
+            neurons: layer.neurons.len(),
+            activation: layer.activation,
+        }))
+        .collect()
+    }
+
+    pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.neurons.iter())
+            .flat_map(|neuron| std::iter::once(&neuron.bias).chain(&neuron.weights))
+            .copied()
+    }
 }
 
+#[derive(Clone, Debug, PartialEq)]
 struct Layer {
     neurons: Vec<Neuron>,
+    activation: Activation,
 }
 
 impl Layer {
-    fn random(rng: &mut dyn rand::RngCore, input_neurons: usize, output_neurons: usize) -> Self {
-        let neurons = (0..output_neurons)
+    fn random(rng: &mut dyn rand::RngCore, input_neurons: usize, output: &LayerTopology) -> Self {
+        let neurons = (0..output.neurons)
             .map(|_| Neuron::random(rng, input_neurons))
             .collect();
-        Self { neurons }
+        Self {
+            neurons,
+            activation: output.activation,
+        }
+    }
+
+    fn from_weights(
+        input_neurons: usize,
+        output: &LayerTopology,
+        weights: &mut dyn Iterator<Item = f32>,
+    ) -> Self {
+        let neurons = (0..output.neurons)
+            .map(|_| Neuron::from_weights(input_neurons, weights))
+            .collect();
+        Self {
+            neurons,
+            activation: output.activation,
+        }
     }
 
     fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
         self.neurons
             .iter()
-            .map(|neuron| neuron.propagate(&inputs))
+            .map(|neuron| neuron.propagate(&inputs, self.activation))
             .collect()
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
 struct Neuron {
     bias: f32,
     weights: Vec<f32>,
@@ -57,19 +191,61 @@ impl Neuron {
         Self { bias, weights }
     }
 
-    fn propagate(&self, inputs: &[f32]) -> f32 {
+    fn from_weights(output_size: usize, weights: &mut dyn Iterator<Item = f32>) -> Self {
+        let bias = weights.next().expect("weight count has been validated");
+        let weights = (0..output_size)
+            .map(|_| weights.next().expect("weight count has been validated"))
+            .collect();
+
+        Self { bias, weights }
+    }
+
+    fn propagate(&self, inputs: &[f32], activation: Activation) -> f32 {
         assert_eq!(inputs.len(), self.weights.len());
         let output = inputs
             .iter()
             .zip(&self.weights)
             .map(|(input, weight)| input * weight)
             .sum::<f32>();
-        (output + self.bias).max(0.0)
+        activation.apply(output + self.bias)
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct LayerTopology {
     pub neurons: usize,
+    // Applied on the outputs of the layer's neurons, ignored for the input layer
+    pub activation: Activation,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Activation {
+    Relu,
+    LeakyRelu { slope: f32 },
+    Tanh,
+    Sigmoid,
+    Linear,
+    Softsign,
+}
+
+impl Activation {
+    pub fn apply(self, x: f32) -> f32 {
+        match self {
+            Self::Relu => x.max(0.0),
+            Self::LeakyRelu { slope } => {
+                if x > 0.0 {
+                    x
+                } else {
+                    slope * x
+                }
+            }
+            Self::Tanh => x.tanh(),
+            Self::Sigmoid => 1.0 / (1.0 + (-x).exp()),
+            Self::Linear => x,
+            Self::Softsign => x / (1.0 + x.abs()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -99,9 +275,9 @@ mod test {
             weights: vec![-0.3, 0.8],
         };
 
-        assert_relative_eq!(neuron.propagate(&[-10.0, -10.0]), 0.0);
+        assert_relative_eq!(neuron.propagate(&[-10.0, -10.0], Activation::Relu), 0.0);
         assert_relative_eq!(
-            neuron.propagate(&[0.5, 1.0]),
+            neuron.propagate(&[0.5, 1.0], Activation::Relu),
             0.5 + 0.5 * (-0.3) + 1.0 * 0.8
         );
     }
@@ -109,9 +285,17 @@ mod test {
     #[test]
     fn random_layer() {
         let mut rng = Cc8::from_seed(Default::default());
-        let layer = Layer::random(&mut rng, 4, 3);
+        let layer = Layer::random(
+            &mut rng,
+            4,
+            &LayerTopology {
+                neurons: 3,
+                activation: Activation::Tanh,
+            },
+        );
 
         assert_eq!(layer.neurons.len(), 3);
+        assert_eq!(layer.activation, Activation::Tanh);
         assert_eq!(layer.neurons[0].weights.len(), 4);
         assert_eq!(layer.neurons[1].weights.len(), 4);
         assert_eq!(layer.neurons[2].weights.len(), 4);
@@ -131,6 +315,7 @@ mod test {
                     weights: vec![-0.5, 0.5, -0.5],
                 },
             ],
+            activation: Activation::Relu,
         };
 
         let output = layer.propagate(vec![0.3, 0.2, -0.1]);
@@ -143,10 +328,22 @@ mod test {
         let network = Network::random(
             &mut rng,
             &[
-                LayerTopology { neurons: 5 },
-                LayerTopology { neurons: 4 },
-                LayerTopology { neurons: 3 },
-                LayerTopology { neurons: 2 },
+                LayerTopology {
+                    neurons: 5,
+                    activation: Activation::Relu,
+                },
+                LayerTopology {
+                    neurons: 4,
+                    activation: Activation::Relu,
+                },
+                LayerTopology {
+                    neurons: 3,
+                    activation: Activation::Relu,
+                },
+                LayerTopology {
+                    neurons: 2,
+                    activation: Activation::Relu,
+                },
             ],
         );
 
@@ -160,9 +357,98 @@ mod test {
         assert_eq!(network.layers[0].neurons[3].weights.len(), 5);
     }
 
+    #[test]
+    fn network_weights_round_trip() {
+        let layers = [
+            LayerTopology {
+                neurons: 3,
+                activation: Activation::Relu,
+            },
+            LayerTopology {
+                neurons: 2,
+                activation: Activation::Relu,
+            },
+            LayerTopology {
+                neurons: 1,
+                activation: Activation::Relu,
+            },
+        ];
+        let weights = (1..=11).map(|n| n as f32 * 0.1).collect::<Vec<f32>>();
+
+        let network = Network::from_weights(&layers, weights.clone()).unwrap();
+
+        assert_relative_eq!(network.layers[0].neurons[0].bias, 0.1);
+        assert_relative_eq!(
+            network.layers[0].neurons[0].weights.as_slice(),
+            [0.2, 0.3, 0.4].as_ref()
+        );
+        assert_relative_eq!(network.layers[1].neurons[0].bias, 0.9);
+
+        let actual = network.weights().collect::<Vec<f32>>();
+        assert_relative_eq!(actual.as_slice(), weights.as_slice());
+    }
+
+    #[test]
+    fn weight_count() {
+        let layers = [
+            LayerTopology {
+                neurons: 5,
+                activation: Activation::Relu,
+            },
+            LayerTopology {
+                neurons: 4,
+                activation: Activation::Relu,
+            },
+            LayerTopology {
+                neurons: 2,
+                activation: Activation::Relu,
+            },
+        ];
+
+        assert_eq!(Network::weight_count(&layers), Ok(6 * 4 + 5 * 2));
+        assert_eq!(
+            Network::weight_count(&layers[..1]),
+            Err(Error::InvalidTopology { layers: 1 })
+        );
+    }
+
+    #[test]
+    fn network_from_invalid_weight_count() {
+        let layers = [
+            LayerTopology {
+                neurons: 3,
+                activation: Activation::Relu,
+            },
+            LayerTopology {
+                neurons: 2,
+                activation: Activation::Relu,
+            },
+        ];
+
+        let too_few = Network::from_weights(&layers, vec![0.0; 7]).err().unwrap();
+        let too_many = Network::from_weights(&layers, vec![0.0; 9]).err().unwrap();
+
+        assert_eq!(
+            too_few,
+            Error::InvalidWeightCount {
+                expected: 8,
+                actual: 7
+            }
+        );
+        assert_eq!(
+            too_many.to_string(),
+            "invalid number of weights: the topology requires 8, got 9"
+        );
+        assert_eq!(
+            Network::from_weights(&layers[..1], vec![]).err(),
+            Some(Error::InvalidTopology { layers: 1 })
+        );
+    }
+
     #[test]
     fn propagate_network() {
         let network = Network {
+            inputs: 3,
             layers: vec![
                 Layer {
                     neurons: vec![
@@ -175,12 +461,14 @@ mod test {
                             weights: vec![-0.5, 0.5, -0.5],
                         },
                     ],
+                    activation: Activation::Relu,
                 },
                 Layer {
                     neurons: vec![Neuron {
                         bias: 0.7,
                         weights: vec![-0.9, -0.1],
                     }],
+                    activation: Activation::Relu,
                 },
             ],
         };
@@ -188,4 +476,48 @@ mod test {
         let output = network.propagate(vec![0.3, 0.2, -0.1]);
         assert_relative_eq!(output.as_slice(), [0.204].as_ref());
     }
+
+    #[test]
+    fn activations() {
+        assert_relative_eq!(Activation::Relu.apply(-2.0), 0.0);
+        assert_relative_eq!(Activation::Relu.apply(2.0), 2.0);
+        assert_relative_eq!(Activation::LeakyRelu { slope: 0.1 }.apply(-2.0), -0.2);
+        assert_relative_eq!(Activation::LeakyRelu { slope: 0.1 }.apply(2.0), 2.0);
+        assert_relative_eq!(Activation::Tanh.apply(-0.5), -0.46211716);
+        assert_relative_eq!(Activation::Sigmoid.apply(0.0), 0.5);
+        assert_relative_eq!(Activation::Sigmoid.apply(2.0), 0.8807971);
+        assert_relative_eq!(Activation::Linear.apply(-3.0), -3.0);
+        assert_relative_eq!(Activation::Softsign.apply(-3.0), -0.75);
+    }
+
+    #[test]
+    fn propagate_network_with_linear_output() {
+        let layers = [
+            LayerTopology {
+                neurons: 2,
+                activation: Activation::Linear,
+            },
+            LayerTopology {
+                neurons: 2,
+                activation: Activation::Relu,
+            },
+            LayerTopology {
+                neurons: 1,
+                activation: Activation::Linear,
+            },
+        ];
+        let weights = vec![
+            0.5, 0.1, 0.2, // hidden neuron #1
+            0.1, -0.5, 0.5, // hidden neuron #2
+            -0.7, -0.9, -0.1, // output neuron
+        ];
+
+        let network = Network::from_weights(&layers, weights).unwrap();
+        let output = network.propagate(vec![0.3, 0.2]);
+
+        let hidden = [0.5 + 0.03 + 0.04, 0.1 - 0.15 + 0.1];
+        assert_eq!(output.len(), 1);
+        assert_relative_eq!(output[0], -0.7 - 0.9 * hidden[0] - 0.1 * hidden[1]);
+        assert!(output[0] < 0.0);
+    }
 }
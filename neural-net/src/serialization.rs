@@ -0,0 +1,326 @@
+use super::{Activation, Error, LayerTopology, Network};
+
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+pub const FORMAT_VERSION: u32 = 1;
+
+// Leading bytes of the binary format
+const MAGIC: &[u8; 4] = b"PCNN";
+
+// On-disk representation shared by the JSON and the binary formats:
+// the topology plus the flattened weights (see `Network::weights`)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NetworkFile {
+    version: u32,
+    topology: Vec<LayerTopology>,
+    weights: Vec<f32>,
+}
+
+impl From<Network> for NetworkFile {
+    fn from(network: Network) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            topology: network.topology(),
+            weights: network.weights().collect(),
+        }
+    }
+}
+
+impl TryFrom<NetworkFile> for Network {
+    type Error = Error;
+
+    fn try_from(file: NetworkFile) -> Result<Self, Self::Error> {
+        if file.version != FORMAT_VERSION {
+            return Err(Error::UnsupportedVersion {
+                version: file.version,
+            });
+        }
+        Network::from_weights(&file.topology, file.weights)
+    }
+}
+
+impl Network {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a network is always serializable")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let file = serde_json::from_str::<NetworkFile>(json)
+            .map_err(|err| Error::Malformed(err.to_string()))?;
+        Network::try_from(file)
+    }
+
+    // Little-endian layout:
+    // magic, version: u32, layer count: u32,
+    // per layer: neurons: u32, activation tag: u8, activation parameter: f32,
+    // weight count: u32, weights: f32...
+    // Fails if a size doesn't fit in a u32
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let topology = self.topology();
+        let weights = self.weights().collect::<Vec<f32>>();
+
+        let mut bytes = Vec::with_capacity(16 + 9 * topology.len() + 4 * weights.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&encode_size(topology.len(), "layers")?);
+        for layer in topology.iter() {
+            let (tag, parameter) = encode_activation(layer.activation);
+            bytes.extend_from_slice(&encode_size(layer.neurons, "neurons")?);
+            bytes.push(tag);
+            bytes.extend_from_slice(&parameter.to_le_bytes());
+        }
+        bytes.extend_from_slice(&encode_size(weights.len(), "weights")?);
+        for weight in weights {
+            bytes.extend_from_slice(&weight.to_le_bytes());
+        }
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = Reader { bytes };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(Error::Malformed("missing magic bytes".to_string()));
+        }
+
+        let version = reader.u32()?;
+        if version != FORMAT_VERSION {
+            return Err(Error::UnsupportedVersion { version });
+        }
+
+        let layers = reader.u32()?;
+        let topology = (0..layers)
+            .map(|_| {
+                let neurons = reader.u32()? as usize;
+                let tag = reader.take(1)?[0];
+                let parameter = reader.f32()?;
+                Ok(LayerTopology {
+                    neurons,
+                    activation: decode_activation(tag, parameter)?,
+                })
+            })
+            .collect::<Result<Vec<LayerTopology>, Error>>()?;
+
+        let weights = reader.u32()?;
+        let weights = (0..weights)
+            .map(|_| reader.f32())
+            .collect::<Result<Vec<f32>, Error>>()?;
+
+        if !reader.bytes.is_empty() {
+            return Err(Error::Malformed(format!(
+                "{} trailing bytes",
+                reader.bytes.len()
+            )));
+        }
+        Network::from_weights(&topology, weights)
+    }
+}
+
+fn encode_size(size: usize, what: &str) -> Result<[u8; 4], Error> {
+    u32::try_from(size)
+        .map(u32::to_le_bytes)
+        .map_err(|_| Error::Malformed(format!("too many {} for the binary format", what)))
+}
+
+fn encode_activation(activation: Activation) -> (u8, f32) {
+    match activation {
+        Activation::Relu => (0, 0.0),
+        Activation::LeakyRelu { slope } => (1, slope),
+        Activation::Tanh => (2, 0.0),
+        Activation::Sigmoid => (3, 0.0),
+        Activation::Linear => (4, 0.0),
+        Activation::Softsign => (5, 0.0),
+    }
+}
+
+fn decode_activation(tag: u8, parameter: f32) -> Result<Activation, Error> {
+    match tag {
+        0 => Ok(Activation::Relu),
+        1 => Ok(Activation::LeakyRelu { slope: parameter }),
+        2 => Ok(Activation::Tanh),
+        3 => Ok(Activation::Sigmoid),
+        4 => Ok(Activation::Linear),
+        5 => Ok(Activation::Softsign),
+        _ => Err(Error::Malformed(format!("unknown activation tag {}", tag))),
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], Error> {
+        if self.bytes.len() < count {
+            return Err(Error::Malformed("unexpected end of data".to_string()));
+        }
+        let (head, tail) = self.bytes.split_at(count);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn f32(&mut self) -> Result<f32, Error> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng as Cc8;
+
+    fn network() -> Network {
+        let mut rng = Cc8::from_seed(Default::default());
+        Network::random(
+            &mut rng,
+            &[
+                LayerTopology {
+                    neurons: 4,
+                    activation: Activation::Linear,
+                },
+                LayerTopology {
+                    neurons: 3,
+                    activation: Activation::LeakyRelu { slope: 0.01 },
+                },
+                LayerTopology {
+                    neurons: 1,
+                    activation: Activation::Tanh,
+                },
+            ],
+        )
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let network = network();
+
+        let json = network.to_json();
+        let restored = Network::from_json(&json).unwrap();
+
+        assert_eq!(restored, network);
+        assert!(json.contains("\"version\": 1"));
+        assert!(json.contains("\"leaky_relu\""));
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let network = network();
+
+        let bytes = network.to_bytes().unwrap();
+        let restored = Network::from_bytes(&bytes).unwrap();
+
+        assert_eq!(restored, network);
+        assert_eq!(&bytes[..4], b"PCNN");
+        assert_eq!(&bytes[4..8], &[1, 0, 0, 0]);
+        assert_eq!(bytes.len(), 4 + 4 + 4 + 3 * 9 + 4 + 4 * (5 * 3 + 4));
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let mut bytes = network().to_bytes().unwrap();
+        bytes[4] = 2;
+        let json = network()
+            .to_json()
+            .replace("\"version\": 1", "\"version\": 7");
+
+        assert_eq!(
+            Network::from_bytes(&bytes),
+            Err(Error::UnsupportedVersion { version: 2 })
+        );
+        assert_eq!(
+            Network::from_json(&json),
+            Err(Error::UnsupportedVersion { version: 7 })
+        );
+    }
+
+    #[test]
+    fn malformed_binary() {
+        let bytes = network().to_bytes().unwrap();
+
+        assert_eq!(
+            Network::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(Error::Malformed("unexpected end of data".to_string()))
+        );
+        assert_eq!(
+            Network::from_bytes(b"JUNK"),
+            Err(Error::Malformed("missing magic bytes".to_string()))
+        );
+    }
+
+    #[test]
+    fn malformed_topology() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&3u32.to_le_bytes());
+        for _ in 0..3 {
+            bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+            bytes.push(4);
+            bytes.extend_from_slice(&0.0f32.to_le_bytes());
+        }
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+
+        assert_eq!(
+            Network::from_bytes(&bytes),
+            Err(Error::Malformed(
+                "the topology has too many weights".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn empty_hidden_layer_round_trip() {
+        let layers = [
+            LayerTopology {
+                neurons: 4,
+                activation: Activation::Linear,
+            },
+            LayerTopology {
+                neurons: 0,
+                activation: Activation::Relu,
+            },
+            LayerTopology {
+                neurons: 1,
+                activation: Activation::Tanh,
+            },
+        ];
+        let network = Network::from_weights(&layers, vec![0.5]).unwrap();
+
+        assert_eq!(network.topology(), layers.to_vec());
+        assert_eq!(Network::from_json(&network.to_json()), Ok(network.clone()));
+        assert_eq!(
+            Network::from_bytes(&network.to_bytes().unwrap()),
+            Ok(network)
+        );
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn too_many_neurons_for_the_binary_format() {
+        let layers = [
+            LayerTopology {
+                neurons: u32::MAX as usize + 1,
+                activation: Activation::Linear,
+            },
+            LayerTopology {
+                neurons: 0,
+                activation: Activation::Relu,
+            },
+        ];
+        let network = Network::from_weights(&layers, vec![]).unwrap();
+
+        assert_eq!(
+            network.to_bytes(),
+            Err(Error::Malformed(
+                "too many neurons for the binary format".to_string()
+            ))
+        );
+    }
+}
@@ -0,0 +1,335 @@
+use super::{Integrator, Parameters, State, StateVector};
+
+use rand::{Rng, RngCore};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Space {
+    // Continuous values with per-dimension bounds (possibly infinite)
+    Box { low: Vec<f32>, high: Vec<f32> },
+    // Integers in `0..n`
+    Discrete(usize),
+}
+
+pub trait Environment {
+    type Observation;
+    type Action;
+    type Info;
+
+    fn reset(&mut self, rng: &mut dyn RngCore) -> Self::Observation;
+    // Returns (observation, reward, done, info)
+    fn step(&mut self, action: Self::Action) -> (Self::Observation, f32, bool, Self::Info);
+    fn observation_space(&self) -> Space;
+    fn action_space(&self) -> Space;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TerminationReason {
+    AngleLimit,
+    TrackLimit,
+    MaxSteps,
+}
+
+// `None` disables the given criterion
+#[derive(Clone, Debug)]
+pub struct Termination {
+    // Maximum absolute pole angle measured from upright [rad]
+    pub angle_limit: Option<f32>,
+    // Maximum absolute cart position [m]
+    pub track_limit: Option<f32>,
+    pub max_steps: Option<usize>,
+}
+
+impl Default for Termination {
+    fn default() -> Self {
+        Self {
+            angle_limit: Some(12f32.to_radians()),
+            track_limit: Some(2.4),
+            max_steps: Some(500),
+        }
+    }
+}
+
+impl Termination {
+    fn check(&self, state: &State, steps: usize) -> Option<TerminationReason> {
+        if matches!(self.angle_limit, Some(limit) if state.pole_angle.abs() > limit) {
+            Some(TerminationReason::AngleLimit)
+        } else if matches!(self.track_limit, Some(limit) if state.cart_position.abs() > limit) {
+            Some(TerminationReason::TrackLimit)
+        } else if matches!(self.max_steps, Some(limit) if steps >= limit) {
+            Some(TerminationReason::MaxSteps)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Info {
+    pub steps: usize,
+    pub termination: Option<TerminationReason>,
+}
+
+pub trait RewardFunction {
+    fn reward(&self, state: &State, force: f32, termination: Option<TerminationReason>) -> f32;
+}
+
+// +1 for every step taken, including the terminating one
+pub struct AliveReward;
+
+impl RewardFunction for AliveReward {
+    fn reward(&self, _state: &State, _force: f32, _termination: Option<TerminationReason>) -> f32 {
+        1.0
+    }
+}
+
+// Alive bonus minus quadratic penalties on the pole angle, the cart position
+// and the control effort, with an extra penalty for leaving the allowed region
+pub struct ShapedReward {
+    pub angle_weight: f32,
+    pub position_weight: f32,
+    pub effort_weight: f32,
+    pub failure_penalty: f32,
+}
+
+impl RewardFunction for ShapedReward {
+    fn reward(&self, state: &State, force: f32, termination: Option<TerminationReason>) -> f32 {
+        let failure = match termination {
+            Some(TerminationReason::AngleLimit) | Some(TerminationReason::TrackLimit) => {
+                self.failure_penalty
+            }
+            _ => 0.0,
+        };
+        1.0 - self.angle_weight * state.pole_angle.powi(2)
+            - self.position_weight * state.cart_position.powi(2)
+            - self.effort_weight * force.powi(2)
+            - failure
+    }
+}
+
+pub struct PoleCart<I, R> {
+    parameters: Parameters,
+    integrator: I,
+    reward: R,
+    termination: Termination,
+    // Simulated time of a single step [s]
+    dt: f32,
+    // Actions are clamped into [-max_force, max_force] [N]
+    max_force: f32,
+    // Initial state variables are drawn uniformly from [-initial_noise, initial_noise]
+    initial_noise: f32,
+    state: State,
+    steps: usize,
+}
+
+impl<I, R> PoleCart<I, R>
+where
+    I: Integrator,
+    R: RewardFunction,
+{
+    pub fn new(
+        parameters: Parameters,
+        integrator: I,
+        reward: R,
+        termination: Termination,
+        dt: f32,
+        max_force: f32,
+    ) -> Self {
+        assert!(dt > 0.0);
+        assert!(max_force >= 0.0);
+        Self {
+            parameters,
+            integrator,
+            reward,
+            termination,
+            dt,
+            max_force,
+            initial_noise: 0.05,
+            state: State::default(),
+            steps: 0,
+        }
+    }
+
+    pub fn with_initial_noise(mut self, initial_noise: f32) -> Self {
+        assert!(initial_noise >= 0.0);
+        self.initial_noise = initial_noise;
+        self
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn parameters(&self) -> &Parameters {
+        &self.parameters
+    }
+}
+
+impl<I, R> Environment for PoleCart<I, R>
+where
+    I: Integrator,
+    R: RewardFunction,
+{
+    type Observation = StateVector;
+    type Action = f32;
+    type Info = Info;
+
+    fn reset(&mut self, rng: &mut dyn RngCore) -> Self::Observation {
+        let noise = self.initial_noise;
+        let mut sample = || {
+            if noise > 0.0 {
+                rng.gen_range(-noise..=noise)
+            } else {
+                0.0
+            }
+        };
+        self.state = State {
+            cart_position: sample(),
+            cart_velocity: sample(),
+            pole_angle: sample(),
+            pole_angular_velocity: sample(),
+        };
+        self.steps = 0;
+        self.state.to_vector()
+    }
+
+    fn step(&mut self, action: Self::Action) -> (Self::Observation, f32, bool, Self::Info) {
+        let force = action.clamp(-self.max_force, self.max_force);
+        self.state
+            .propagate_dynamics(&self.parameters, &self.integrator, force, self.dt);
+        self.steps += 1;
+
+        let termination = self.termination.check(&self.state, self.steps);
+        let reward = self.reward.reward(&self.state, force, termination);
+        let info = Info {
+            steps: self.steps,
+            termination,
+        };
+        (self.state.to_vector(), reward, termination.is_some(), info)
+    }
+
+    fn observation_space(&self) -> Space {
+        let track = self.termination.track_limit.unwrap_or(f32::INFINITY);
+        let angle = self
+            .termination
+            .angle_limit
+            .unwrap_or(std::f32::consts::PI)
+            .min(std::f32::consts::PI);
+        Space::Box {
+            low: vec![-track, f32::NEG_INFINITY, -angle, f32::NEG_INFINITY],
+            high: vec![track, f32::INFINITY, angle, f32::INFINITY],
+        }
+    }
+
+    fn action_space(&self) -> Space {
+        Space::Box {
+            low: vec![-self.max_force],
+            high: vec![self.max_force],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::RungeKutta4;
+
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng as Cc8;
+
+    fn environment(termination: Termination) -> PoleCart<RungeKutta4, AliveReward> {
+        PoleCart::new(
+            Parameters::default(),
+            RungeKutta4,
+            AliveReward,
+            termination,
+            0.02,
+            10.0,
+        )
+    }
+
+    #[test]
+    fn reset_samples_small_initial_state() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let mut environment = environment(Termination::default());
+
+        let observation = environment.reset(&mut rng);
+
+        assert!(observation.iter().all(|x| x.abs() <= 0.05));
+        assert!(observation.iter().any(|x| *x != 0.0));
+        assert_eq!(environment.state().to_vector(), observation);
+    }
+
+    #[test]
+    fn unbalanced_pole_hits_the_angle_limit() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let mut environment = environment(Termination::default());
+        environment.reset(&mut rng);
+
+        let mut total_reward = 0.0;
+        let info = loop {
+            let (_, reward, done, info) = environment.step(0.0);
+            total_reward += reward;
+            if done {
+                break info;
+            }
+        };
+
+        assert_eq!(info.termination, Some(TerminationReason::AngleLimit));
+        assert!(info.steps < 500);
+        assert!((total_reward - info.steps as f32).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn pushed_cart_hits_the_track_limit() {
+        let mut environment = environment(Termination {
+            angle_limit: None,
+            ..Termination::default()
+        });
+
+        let info = loop {
+            let (_, _, done, info) = environment.step(100.0);
+            if done {
+                break info;
+            }
+        };
+
+        assert_eq!(info.termination, Some(TerminationReason::TrackLimit));
+    }
+
+    #[test]
+    fn episode_is_truncated_after_max_steps() {
+        let mut environment = environment(Termination {
+            angle_limit: None,
+            track_limit: None,
+            max_steps: Some(10),
+        });
+
+        let dones = (0..10)
+            .map(|_| environment.step(0.0).2)
+            .collect::<Vec<bool>>();
+
+        assert!(dones[..9].iter().all(|done| !done));
+        assert!(dones[9]);
+    }
+
+    #[test]
+    fn shaped_reward_penalizes_deviation_and_failure() {
+        let reward = ShapedReward {
+            angle_weight: 1.0,
+            position_weight: 0.5,
+            effort_weight: 0.01,
+            failure_penalty: 10.0,
+        };
+        let state = State {
+            cart_position: 1.0,
+            pole_angle: 0.5,
+            ..State::default()
+        };
+
+        let alive = reward.reward(&state, 2.0, None);
+        let failed = reward.reward(&state, 2.0, Some(TerminationReason::TrackLimit));
+
+        assert!((alive - (1.0 - 0.25 - 0.5 - 0.04)).abs() < 1e-6);
+        assert!((failed - (alive - 10.0)).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,199 @@
+// The state vector is laid out as (coordinate, rate) pairs, i.e.
+// [cart position, cart velocity, pole angle, pole angular velocity]
+pub type StateVector = [f32; 4];
+
+pub trait Integrator {
+    fn integrate(
+        &self,
+        derivative: &dyn Fn(&StateVector) -> StateVector,
+        state: StateVector,
+        dt: f32,
+    ) -> StateVector;
+}
+
+fn add_scaled(state: &StateVector, rates: &[(f32, StateVector)]) -> StateVector {
+    let mut result = *state;
+    for (scale, rate) in rates {
+        result
+            .iter_mut()
+            .zip(rate.iter())
+            .for_each(|(x, r)| *x += scale * r);
+    }
+    result
+}
+
+pub struct ExplicitEuler;
+
+impl Integrator for ExplicitEuler {
+    fn integrate(
+        &self,
+        derivative: &dyn Fn(&StateVector) -> StateVector,
+        state: StateVector,
+        dt: f32,
+    ) -> StateVector {
+        add_scaled(&state, &[(dt, derivative(&state))])
+    }
+}
+
+// Updates the rates first and then moves the coordinates with the already
+// updated rates (symplectic Euler)
+pub struct SemiImplicitEuler;
+
+impl Integrator for SemiImplicitEuler {
+    fn integrate(
+        &self,
+        derivative: &dyn Fn(&StateVector) -> StateVector,
+        state: StateVector,
+        dt: f32,
+    ) -> StateVector {
+        let rates = derivative(&state);
+        let mut next = state;
+        for i in (0..next.len()).step_by(2) {
+            next[i + 1] += rates[i + 1] * dt;
+            next[i] += next[i + 1] * dt;
+        }
+        next
+    }
+}
+
+pub struct RungeKutta4;
+
+impl Integrator for RungeKutta4 {
+    fn integrate(
+        &self,
+        derivative: &dyn Fn(&StateVector) -> StateVector,
+        state: StateVector,
+        dt: f32,
+    ) -> StateVector {
+        let k1 = derivative(&state);
+        let k2 = derivative(&add_scaled(&state, &[(0.5 * dt, k1)]));
+        let k3 = derivative(&add_scaled(&state, &[(0.5 * dt, k2)]));
+        let k4 = derivative(&add_scaled(&state, &[(dt, k3)]));
+        add_scaled(
+            &state,
+            &[
+                (dt / 6.0, k1),
+                (dt / 3.0, k2),
+                (dt / 3.0, k3),
+                (dt / 6.0, k4),
+            ],
+        )
+    }
+}
+
+// Adaptive Runge-Kutta 4(5) method that takes as many sub-steps within `dt`
+// as needed to keep the estimated local error below the given tolerance
+pub struct DormandPrince {
+    tolerance: f32,
+    min_step: f32,
+}
+
+impl DormandPrince {
+    pub fn new(tolerance: f32, min_step: f32) -> Self {
+        assert!(tolerance > 0.0);
+        assert!(min_step > 0.0);
+        Self {
+            tolerance,
+            min_step,
+        }
+    }
+
+    fn step(
+        derivative: &dyn Fn(&StateVector) -> StateVector,
+        state: &StateVector,
+        h: f32,
+    ) -> (StateVector, StateVector) {
+        let k1 = derivative(state);
+        let k2 = derivative(&add_scaled(state, &[(h / 5.0, k1)]));
+        let k3 = derivative(&add_scaled(
+            state,
+            &[(h * 3.0 / 40.0, k1), (h * 9.0 / 40.0, k2)],
+        ));
+        let k4 = derivative(&add_scaled(
+            state,
+            &[
+                (h * 44.0 / 45.0, k1),
+                (-h * 56.0 / 15.0, k2),
+                (h * 32.0 / 9.0, k3),
+            ],
+        ));
+        let k5 = derivative(&add_scaled(
+            state,
+            &[
+                (h * 19372.0 / 6561.0, k1),
+                (-h * 25360.0 / 2187.0, k2),
+                (h * 64448.0 / 6561.0, k3),
+                (-h * 212.0 / 729.0, k4),
+            ],
+        ));
+        let k6 = derivative(&add_scaled(
+            state,
+            &[
+                (h * 9017.0 / 3168.0, k1),
+                (-h * 355.0 / 33.0, k2),
+                (h * 46732.0 / 5247.0, k3),
+                (h * 49.0 / 176.0, k4),
+                (-h * 5103.0 / 18656.0, k5),
+            ],
+        ));
+        let fifth_order = add_scaled(
+            state,
+            &[
+                (h * 35.0 / 384.0, k1),
+                (h * 500.0 / 1113.0, k3),
+                (h * 125.0 / 192.0, k4),
+                (-h * 2187.0 / 6784.0, k5),
+                (h * 11.0 / 84.0, k6),
+            ],
+        );
+        let k7 = derivative(&fifth_order);
+        // difference between the 5th and the embedded 4th order solutions
+        let error = add_scaled(
+            &[0.0; 4],
+            &[
+                (h * 71.0 / 57600.0, k1),
+                (-h * 71.0 / 16695.0, k3),
+                (h * 71.0 / 1920.0, k4),
+                (-h * 17253.0 / 339200.0, k5),
+                (h * 22.0 / 525.0, k6),
+                (-h / 40.0, k7),
+            ],
+        );
+        (fifth_order, error)
+    }
+}
+
+impl Integrator for DormandPrince {
+    fn integrate(
+        &self,
+        derivative: &dyn Fn(&StateVector) -> StateVector,
+        state: StateVector,
+        dt: f32,
+    ) -> StateVector {
+        let mut state = state;
+        let mut elapsed = 0.0;
+        let mut h = dt;
+        while dt - elapsed > f32::EPSILON * dt {
+            h = h.min(dt - elapsed);
+            let (next, error) = Self::step(derivative, &state, h);
+            let error_norm = error
+                .iter()
+                .zip(next.iter())
+                .map(|(e, x)| e.abs() / (self.tolerance * (1.0 + x.abs())))
+                .fold(0.0, f32::max);
+
+            if error_norm <= 1.0 || h <= self.min_step {
+                state = next;
+                elapsed += h;
+            }
+
+            let factor = if error_norm > 0.0 {
+                0.9 * error_norm.powf(-0.2)
+            } else {
+                5.0
+            };
+            h = (h * factor.clamp(0.2, 5.0)).max(self.min_step);
+        }
+        state
+    }
+}
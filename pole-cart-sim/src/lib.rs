@@ -0,0 +1,218 @@
+mod environment;
+mod integrator;
+
+pub use environment::{
+    AliveReward, Environment, Info, PoleCart, RewardFunction, ShapedReward, Space, Termination,
+    TerminationReason,
+};
+pub use integrator::{
+    DormandPrince, ExplicitEuler, Integrator, RungeKutta4, SemiImplicitEuler, StateVector,
+};
+
+use std::f32::consts::{PI, TAU};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct State {
+    pub cart_position: f32,
+    pub cart_velocity: f32,
+    pub pole_angle: f32,
+    pub pole_angular_velocity: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct Parameters {
+    pub cart_mass: f32,
+    pub pole_mass: f32,
+    // The pole is modelled as a homogeneous rod hinged at its bottom end
+    pub pole_length: f32,
+    pub gravity: f32,
+    // Viscous friction between the cart and the track [N s / m]
+    pub cart_drag_coefficient: f32,
+    // Viscous friction in the hinge of the pole [N m s / rad]
+    pub pole_drag_coefficient: f32,
+}
+
+impl Default for Parameters {
+    fn default() -> Self {
+        Self {
+            cart_mass: 1.0,
+            pole_mass: 0.5,
+            pole_length: 2.0,
+            gravity: 9.81,
+            cart_drag_coefficient: 0.0,
+            pole_drag_coefficient: 0.0,
+        }
+    }
+}
+
+impl Parameters {
+    pub fn pole_half_length(&self) -> f32 {
+        0.5 * self.pole_length
+    }
+
+    pub fn total_mass(&self) -> f32 {
+        self.cart_mass + self.pole_mass
+    }
+
+    // Moment of inertia of the pole around its center of mass
+    pub fn pole_inertia(&self) -> f32 {
+        self.pole_mass * self.pole_length * self.pole_length / 12.0
+    }
+}
+
+impl State {
+    pub fn propagate_dynamics<I>(
+        &mut self,
+        parameters: &Parameters,
+        integrator: &I,
+        input_force: f32,
+        dt: f32,
+    ) where
+        I: Integrator + ?Sized,
+    {
+        let derivative = |state: &StateVector| Self::derivative(parameters, input_force, state);
+        let next = integrator.integrate(&derivative, self.to_vector(), dt);
+
+        self.cart_position = next[0];
+        self.cart_velocity = next[1];
+        self.pole_angle = next[2];
+        self.pole_angular_velocity = next[3];
+
+        if self.pole_angle < -PI {
+            self.pole_angle += TAU;
+        } else if self.pole_angle >= PI {
+            self.pole_angle -= TAU;
+        }
+    }
+
+    // Kinetic plus potential energy, the latter measured from the pivot
+    pub fn energy(&self, parameters: &Parameters) -> f32 {
+        let half_length = parameters.pole_half_length();
+        let pole_mass_length = parameters.pole_mass * half_length;
+        let ca = self.pole_angle.cos();
+
+        let kinetic = 0.5 * parameters.total_mass() * self.cart_velocity.powi(2)
+            + pole_mass_length * self.cart_velocity * self.pole_angular_velocity * ca
+            + 0.5
+                * (parameters.pole_inertia() + pole_mass_length * half_length)
+                * self.pole_angular_velocity.powi(2);
+        let potential = parameters.gravity * pole_mass_length * ca;
+
+        kinetic + potential
+    }
+
+    pub fn to_vector(&self) -> StateVector {
+        [
+            self.cart_position,
+            self.cart_velocity,
+            self.pole_angle,
+            self.pole_angular_velocity,
+        ]
+    }
+
+    fn derivative(parameters: &Parameters, input_force: f32, state: &StateVector) -> StateVector {
+        let &[_, cart_velocity, pole_angle, pole_angular_velocity] = state;
+        let Parameters {
+            cart_mass,
+            pole_mass,
+            gravity,
+            cart_drag_coefficient,
+            pole_drag_coefficient,
+            ..
+        } = *parameters;
+        let half_length = parameters.pole_half_length();
+        let all_mass = parameters.total_mass();
+        let inertia = parameters.pole_inertia();
+        let pole_mass_length = pole_mass * half_length;
+        let pole_mass_length_2 = pole_mass_length * half_length;
+
+        let (sa, ca) = pole_angle.sin_cos();
+        let denominator =
+            inertia * all_mass + pole_mass_length_2 * (cart_mass + pole_mass * sa * sa);
+
+        let force = input_force - cart_drag_coefficient * cart_velocity;
+        let torque = pole_drag_coefficient * pole_angular_velocity;
+        let aux = pole_mass_length * pole_angular_velocity.powi(2) * sa;
+        let acc_nominator = (inertia + pole_mass_length_2) * (force + aux)
+            - pole_mass_length * ca * (gravity * pole_mass_length * sa - torque);
+
+        let ang_acc_nominator = all_mass * (gravity * pole_mass_length * sa - torque)
+            - pole_mass_length * ca * (force + aux);
+
+        [
+            cart_velocity,
+            acc_nominator / denominator,
+            pole_angular_velocity,
+            ang_acc_nominator / denominator,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Relative energy drift of the undamped, unforced system after 10 seconds
+    fn energy_drift<I: Integrator>(integrator: &I, dt: f32) -> f32 {
+        let parameters = Parameters::default();
+        let mut state = State {
+            pole_angle: PI - 0.5,
+            ..State::default()
+        };
+
+        let initial_energy = state.energy(&parameters);
+        let lowest_energy =
+            -parameters.gravity * parameters.pole_mass * parameters.pole_half_length();
+        let swing = initial_energy - lowest_energy;
+        for _ in 0..(10.0 / dt) as usize {
+            state.propagate_dynamics(&parameters, integrator, 0.0, dt);
+        }
+        (state.energy(&parameters) - initial_energy).abs() / swing
+    }
+
+    #[test]
+    fn explicit_euler_energy_drift() {
+        let drift = energy_drift(&ExplicitEuler, 0.01);
+        assert!(drift > 0.5);
+    }
+
+    #[test]
+    fn semi_implicit_euler_energy_drift() {
+        let drift = energy_drift(&SemiImplicitEuler, 0.01);
+        assert!(drift < 1e-2);
+    }
+
+    #[test]
+    fn runge_kutta_4_energy_drift() {
+        let drift = energy_drift(&RungeKutta4, 0.01);
+        assert!(drift < 1e-4);
+    }
+
+    #[test]
+    fn dormand_prince_energy_drift() {
+        let drift = energy_drift(&DormandPrince::new(1e-5, 1e-4), 0.05);
+        assert!(drift < 1e-5);
+    }
+
+    #[test]
+    fn drag_dissipates_energy() {
+        let parameters = Parameters {
+            cart_drag_coefficient: 0.5,
+            pole_drag_coefficient: 0.1,
+            ..Parameters::default()
+        };
+        let mut state = State {
+            cart_velocity: 1.0,
+            pole_angle: PI - 0.5,
+            ..State::default()
+        };
+
+        let mut energy = state.energy(&parameters);
+        for _ in 0..100 {
+            state.propagate_dynamics(&parameters, &RungeKutta4, 0.0, 0.01);
+            let next_energy = state.energy(&parameters);
+            assert!(next_energy < energy);
+            energy = next_energy;
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use genetic_algorithm::{Chromosome, Decoder, Evaluated, Evaluation, Evaluator};
+use neural_net::{Activation, LayerTopology, Network};
+use pole_cart_sim::{
+    AliveReward, Environment, Parameters, PoleCart, RungeKutta4, StateVector, Termination,
+};
+
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng as Cc8;
+
+// Observation (cart position, cart velocity, pole angle, pole angular velocity)
+// goes in, the force relative to `MAX_FORCE` comes out
+pub const TOPOLOGY: &[LayerTopology] = &[
+    LayerTopology {
+        neurons: 4,
+        activation: Activation::Linear,
+    },
+    LayerTopology {
+        neurons: 8,
+        activation: Activation::Tanh,
+    },
+    LayerTopology {
+        neurons: 1,
+        activation: Activation::Tanh,
+    },
+];
+
+pub const MAX_FORCE: f32 = 10.0;
+pub const TIME_STEP: f32 = 0.02;
+// Every controller is scored on the same set of initial conditions so that
+// fitness values are comparable and deterministic
+pub const EPISODES: u64 = 5;
+
+// A chromosome decoded into a network by `WeightsDecoder` and scored by
+// `Rollouts`, see `controllers`
+pub type Controller = Evaluated<Network>;
+
+// The chromosome holds the weights of a network with `TOPOLOGY`
+pub struct WeightsDecoder;
+
+impl Decoder for WeightsDecoder {
+    type Phenotype = Network;
+
+    fn decode(&self, chromosome: &Chromosome) -> Network {
+        Network::from_weights(TOPOLOGY, chromosome.iter().copied())
+            .expect("chromosome doesn't match the network topology")
+    }
+}
+
+// Balancing time as the fitness, along with the negated control effort and
+// cart drift as further objectives, see `evaluate_objectives`
+pub struct Rollouts;
+
+impl Evaluator<Network> for Rollouts {
+    fn evaluate(&self, network: &Network) -> Evaluation {
+        let objectives = evaluate_objectives(network);
+        Evaluation::new(objectives[0]).with_objectives(objectives)
+    }
+}
+
+// Controllers with random networks, evaluated as one batch by `evaluator`
+pub fn controllers<E>(rng: &mut dyn RngCore, count: usize, evaluator: &E) -> Vec<Controller>
+where
+    E: Evaluator<Network>,
+{
+    let chromosomes = (0..count)
+        .map(|_| Network::random(rng, TOPOLOGY).weights().collect())
+        .collect();
+    Evaluated::evaluate_all(&WeightsDecoder, evaluator, chromosomes)
+}
+
+pub fn environment() -> PoleCart<RungeKutta4, AliveReward> {
+    PoleCart::new(
+        Parameters::default(),
+        RungeKutta4,
+        AliveReward,
+        Termination::default(),
+        TIME_STEP,
+        MAX_FORCE,
+    )
+}
+
+pub fn act(network: &Network, observation: &StateVector) -> f32 {
+    let output = network.propagate(observation.to_vec());
+    MAX_FORCE * output[0]
+}
+
+// Mean episode reward, i.e. the average number of steps the pole was balanced
+pub fn evaluate(network: &Network) -> f32 {
+    evaluate_objectives(network)[0]
+}
+
+// Mean episode reward, mean absolute force and mean absolute cart position
+// over the episodes, the last two negated so that every objective is maximized
+pub fn evaluate_objectives(network: &Network) -> Vec<f32> {
+    let mut environment = environment();
+    let mut objectives = vec![0.0; 3];
+    for episode in 0..EPISODES {
+        let mut rng = Cc8::seed_from_u64(episode);
+        let mut observation = environment.reset(&mut rng);
+        let (mut episode_reward, mut effort, mut drift, mut steps) = (0.0, 0.0, 0.0, 0.0);
+        loop {
+            let force = act(network, &observation);
+            let (next, reward, done, _) = environment.step(force);
+            episode_reward += reward;
+            effort += force.abs();
+            drift += next[0].abs();
+            steps += 1.0;
+            observation = next;
+            if done {
+                break;
+            }
+        }
+        objectives[0] += episode_reward;
+        objectives[1] -= effort / steps;
+        objectives[2] -= drift / steps;
+    }
+    objectives
+        .into_iter()
+        .map(|objective| objective / EPISODES as f32)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use genetic_algorithm::Individual;
+
+    #[test]
+    fn chromosome_maps_to_network_weights() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let controller = &controllers(&mut rng, 1, &Rollouts)[0];
+
+        let genes = controller
+            .chromosome()
+            .iter()
+            .copied()
+            .collect::<Vec<f32>>();
+        let weights = controller.phenotype().weights().collect::<Vec<f32>>();
+        assert_eq!(genes, weights);
+        assert_eq!(genes.len(), 4 * 8 + 8 + 8 + 1);
+    }
+
+    #[test]
+    fn fitness_is_deterministic() {
+        let mut rng = Cc8::from_seed(Default::default());
+        let controller = &controllers(&mut rng, 1, &Rollouts)[0];
+        let copy = Rollouts.evaluate(&WeightsDecoder.decode(controller.chromosome()));
+
+        assert!(controller.fitness() >= 1.0);
+        assert!(controller.fitness() <= 500.0);
+        assert_eq!(controller.evaluation(), &copy);
+        assert_eq!(controller.objectives().len(), 3);
+        assert!((evaluate(controller.phenotype()) - controller.fitness()).abs() < f32::EPSILON);
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(feature = "parallel")]
+use genetic_algorithm::ParallelEvaluator;
+use genetic_algorithm::{
+    Checkpoint, Evolution, GeneticAlgorithm, Individual, RouletteWheelSelection, UniformCrossover,
+    UniformMutation,
+};
+use neural_net::Network;
+use trainer::{controllers, Controller, Rollouts, WeightsDecoder, TOPOLOGY};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng as Cc8;
+use std::error::Error;
+use std::path::Path;
+
+const POPULATION_SIZE: usize = 100;
+
+// Usage: trainer [generations] [output path] [checkpoint path]
+// With a checkpoint path the state of the run is saved after every
+// generation, and an interrupted run is resumed from an existing checkpoint
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = std::env::args().skip(1);
+    let generations = args
+        .next()
+        .map(|arg| arg.parse::<usize>().expect("invalid number of generations"))
+        .unwrap_or(100);
+    let output = args
+        .next()
+        .unwrap_or_else(|| "best_controller.json".to_string());
+    let checkpoint = args.next();
+
+    #[cfg(feature = "parallel")]
+    let evaluator = ParallelEvaluator::new(Rollouts);
+    #[cfg(not(feature = "parallel"))]
+    let evaluator = Rollouts;
+
+    let (ga, rng, population) = match &checkpoint {
+        Some(path) if Path::new(path).exists() => {
+            let checkpoint = Checkpoint::load(path)?;
+            println!("resuming from generation {}", checkpoint.generation());
+            checkpoint.resume_evaluated(&WeightsDecoder)
+        }
+        _ => {
+            let mut rng = Cc8::from_seed(Default::default());
+            let ga = GeneticAlgorithm::new(
+                UniformCrossover::new(),
+                UniformMutation::new(0.1, 0.3),
+                RouletteWheelSelection::new(),
+            )
+            .with_elitism(2);
+            let population = controllers(&mut rng, POPULATION_SIZE, &evaluator);
+            (ga, rng, population)
+        }
+    };
+
+    let best = fittest(&population);
+    let mut best_fitness = best.fitness();
+    let mut best = best.chromosome().iter().copied().collect::<Vec<f32>>();
+    let mut checkpoint_error = None;
+
+    let mut evolution = Evolution::new(ga, population, rng)
+        .with_max_generations(generations)
+        .on_generation(|evolution, statistics| {
+            println!(
+                "generation {:4}: max {:7.2}, mean {:7.2}, median {:7.2}, std dev {:7.2}, diversity {:.3}",
+                evolution.generation() - 1,
+                statistics.max_fitness(),
+                statistics.mean_fitness(),
+                statistics.median_fitness(),
+                statistics.std_dev_fitness(),
+                statistics.diversity()
+            );
+            if let Some(path) = &checkpoint {
+                if let Err(err) = Checkpoint::save(
+                    path,
+                    evolution.algorithm(),
+                    evolution.rng(),
+                    evolution.population(),
+                ) {
+                    // continuing without checkpoints would defeat their purpose
+                    eprintln!("failed to save the checkpoint to {}: {}", path, err);
+                    evolution.request_stop();
+                    checkpoint_error = Some(err);
+                }
+            }
+        })
+        .on_new_best(|_, controller| {
+            best_fitness = controller.fitness();
+            best = controller.chromosome().iter().copied().collect();
+        });
+    evolution.run_evaluated(&WeightsDecoder, &evaluator);
+    drop(evolution);
+
+    if let Some(err) = checkpoint_error {
+        return Err(err.into());
+    }
+
+    let network = Network::from_weights(TOPOLOGY, best).expect("got an invalid chromosome");
+    std::fs::write(&output, network.to_json())?;
+    println!(
+        "best controller ({:.2}) written to {}",
+        best_fitness, output
+    );
+    Ok(())
+}
+
+fn fittest(population: &[Controller]) -> &Controller {
+    population
+        .iter()
+        .max_by(|a, b| a.fitness().partial_cmp(&b.fitness()).unwrap())
+        .expect("got an empty population")
+}
@@ -1,8 +1,10 @@
-use visualization::{KissScene, State};
 use std::time::Instant;
+use pole_cart_sim::{Parameters, RungeKutta4, State};
+use visualization::KissScene;
 
 fn main() {
-    let mut scene = KissScene::default();
+    let parameters = Parameters::default();
+    let mut scene = KissScene::new(&parameters);
     let mut state = State {
         cart_position: 0.0,
         cart_velocity: 0.0,
@@ -21,7 +23,7 @@ fn main() {
         let delta_dynamics = current_time.duration_since(last_dynamics_time).as_millis() as f32;
         if delta_dynamics >= 1e-2 {
             last_dynamics_time = current_time;
-            state.propagate_dynamics(0.1, delta_dynamics / 1000.0);
+            state.propagate_dynamics(&parameters, &RungeKutta4, 0.1, delta_dynamics / 1000.0);
         } else if delta_render >= ms_per_frame {
             last_render_time = current_time;
             if !scene.render(&state) {
@@ -3,32 +3,28 @@ use kiss3d::light::Light;
 use kiss3d::nalgebra::{Point3, Translation3, UnitQuaternion, Vector3};
 use kiss3d::scene::SceneNode;
 use kiss3d::window::Window;
+use pole_cart_sim::{Parameters, State};
 
-const POLE_HALF_LENGTH: f32 = 1.0;
-const POLE_LENGTH: f32 = 2.0 * POLE_HALF_LENGTH;
-const POLE_Z_SHIFT: f32 = POLE_HALF_LENGTH + 0.11;
-const POLE_MASS: f32 = 0.5;
-const CART_MASS: f32 = 1.0;
-const ALL_MASS: f32 = CART_MASS + POLE_MASS;
-const POLE_MASS_LENGTH: f32 = POLE_MASS * POLE_HALF_LENGTH;
-const POLE_MASS_LENGTH_2: f32 = POLE_MASS_LENGTH * POLE_HALF_LENGTH;
-const POLE_MASS_2_LENGTH_2: f32 = POLE_MASS_LENGTH * POLE_MASS_LENGTH;
-const POLE_X_INERTIA: f32 = POLE_MASS_LENGTH / 3.0;
-const INERTIA_ALL_MASS: f32 = POLE_X_INERTIA * ALL_MASS;
-const G: f32 = 9.81;
-
-use std::f32::consts::{PI, TAU};
+const CART_HALF_HEIGHT: f32 = 0.1;
+const POLE_CLEARANCE: f32 = 0.01;
 
 pub struct KissScene {
     camera: ArcBall,
     window: Window,
     cart: SceneNode,
     pole: SceneNode,
+    pole_z_shift: f32,
     _ground: SceneNode,
 }
 
 impl Default for KissScene {
     fn default() -> Self {
+        Self::new(&Parameters::default())
+    }
+}
+
+impl KissScene {
+    pub fn new(parameters: &Parameters) -> Self {
         let eye = Point3::<f32>::new(10.0, 10.0, 5.0);
         let look_at = Point3::<f32>::new(0.1, 0.1, 0.1);
 
@@ -42,31 +38,31 @@ impl Default for KissScene {
         let mut ground = window.add_cube(100.0, 100.0, 0.1);
         ground.set_local_translation(Translation3::<f32>::new(0.0, 0.0, -1.0));
 
-        let mut cart = window.add_cube(0.8, 1.2, 0.2);
+        let mut cart = window.add_cube(0.8, 1.2, 2.0 * CART_HALF_HEIGHT);
         cart.set_color(0.5, 0.1, 0.7);
 
-        let mut pole = window.add_cube(0.05, 0.05, POLE_LENGTH);
+        let pole_z_shift = parameters.pole_half_length() + CART_HALF_HEIGHT + POLE_CLEARANCE;
+        let mut pole = window.add_cube(0.05, 0.05, parameters.pole_length);
         pole.set_color(0.1, 0.5, 0.4);
-        pole.set_local_translation(Translation3::<f32>::new(0.0, 0.0, POLE_Z_SHIFT));
+        pole.set_local_translation(Translation3::<f32>::new(0.0, 0.0, pole_z_shift));
 
         Self {
             camera,
             window,
             cart,
             pole,
+            pole_z_shift,
             _ground: ground,
         }
     }
-}
 
-impl KissScene {
     pub fn render(&mut self, state: &State) -> bool {
         self.cart
             .set_local_translation(Translation3::new(0.0, state.cart_position, 0.0));
 
         let (sa, ca) = state.pole_angle.sin_cos();
-        let pole_y_pos = state.cart_position - POLE_Z_SHIFT * sa;
-        let pole_z_pos = POLE_Z_SHIFT * ca;
+        let pole_y_pos = state.cart_position - self.pole_z_shift * sa;
+        let pole_z_pos = self.pole_z_shift * ca;
 
         self.pole
             .set_local_translation(Translation3::new(0.0, pole_y_pos, pole_z_pos));
@@ -94,42 +90,3 @@ impl KissScene {
         self.window.render_with_camera(&mut self.camera)
     }
 }
-
-#[derive(Default)]
-pub struct State {
-    pub cart_position: f32,
-    pub cart_velocity: f32,
-    pub pole_angle: f32,
-    pub pole_angular_velocity: f32,
-}
-
-// TODO add drag
-//pub struct Parameters {
-//    pub pole_drag_coefficient: f32,
-//    pub cart_drag_coefficient: f32,
-//}
-
-impl State {
-    pub fn propagate_dynamics(&mut self, input_force: f32, dt: f32) {
-        let (sa, ca) = self.pole_angle.sin_cos();
-        let denominator = INERTIA_ALL_MASS + POLE_MASS_LENGTH_2 * (CART_MASS + POLE_MASS * sa * sa);
-
-        let aux = POLE_MASS_LENGTH * self.pole_angular_velocity.powi(2) * sa;
-        let acc_nominator = (POLE_X_INERTIA + POLE_MASS_LENGTH_2) * (input_force + aux)
-            - G * POLE_MASS_2_LENGTH_2 * sa * ca;
-
-        let ang_acc_nominator =
-            -POLE_MASS_LENGTH * (input_force * ca + aux * ca - ALL_MASS * G * sa);
-
-        self.cart_position += self.cart_velocity * dt;
-        self.cart_velocity += acc_nominator * dt / denominator;
-        self.pole_angle += self.pole_angular_velocity * dt;
-        self.pole_angular_velocity += ang_acc_nominator * dt / denominator;
-
-        if self.pole_angle < -PI {
-            self.pole_angle += TAU;
-        } else if self.pole_angle >= PI {
-            self.pole_angle -= TAU;
-        }
-    }
-}